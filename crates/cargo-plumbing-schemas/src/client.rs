@@ -0,0 +1,601 @@
+//! Typed invocation of `cargo-plumbing` commands
+//!
+//! [`PlumbingCommand`] spawns a `cargo-plumbing` subcommand, feeds it input messages and parses
+//! its output messages. Input is written and stderr is read on separate threads while stdout is
+//! being parsed, so a command blocking on a full pipe can't deadlock the caller.
+//!
+//! The binary is discovered from the [`CARGO_PLUMBING_BIN`] environment variable, falling back
+//! to `cargo-plumbing` in `PATH`. Use [`PlumbingCommand::program`] to set it explicitly.
+//!
+//! Commands are run with `--message-format-version` set to [`MESSAGE_FORMAT_VERSION`], so a
+//! binary speaking another version of the message format fails instead of printing messages this
+//! library can't parse. A line of output that doesn't parse is an error as well.
+//!
+//! ```no_run
+//! use cargo_plumbing_schemas::client::PlumbingCommand;
+//!
+//! let manifests = PlumbingCommand::read_manifest("Cargo.toml")
+//!     .workspace()
+//!     .output()?
+//!     .into_messages()?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::env;
+use std::ffi::{OsStr, OsString};
+use std::io::{self, BufRead, Read, Write};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::locate_manifest::LocateManifestOut;
+use crate::lock_dependencies::{LockDependenciesIn, LockDependenciesOut};
+use crate::plan_build::{PlanBuildIn, PlanBuildOut};
+use crate::query_index::QueryIndexOut;
+use crate::read_lockfile::ReadLockfileOut;
+use crate::read_manifest::{ReadManifestIn, ReadManifestOut};
+use crate::resolve_features::{ResolveFeaturesIn, ResolveFeaturesOut};
+use crate::vendor::{VendorIn, VendorOut};
+use crate::write_lockfile::WriteLockfileIn;
+use crate::{SchemaVersion, MESSAGE_FORMAT_VERSION};
+
+/// Environment variable pointing to the `cargo-plumbing` binary to run.
+pub const CARGO_PLUMBING_BIN: &str = "CARGO_PLUMBING_BIN";
+
+/// Builder for invoking a `cargo-plumbing` subcommand
+///
+/// Each subcommand has its own constructor, e.g. [`PlumbingCommand::lock_dependencies`], which
+/// fixes the message types and only exposes the arguments that subcommand understands.
+///
+/// # Type Parameters
+///
+/// - `O`: The output message type of the subcommand.
+/// - `I`: The input message type of the subcommand, `()` for subcommands without input.
+pub struct PlumbingCommand<O, I = ()> {
+    subcommand: &'static str,
+    program: Option<PathBuf>,
+    args: Vec<OsString>,
+    current_dir: Option<PathBuf>,
+    envs: Vec<(OsString, OsString)>,
+    _m: PhantomData<fn(I) -> O>,
+}
+
+impl<O, I> PlumbingCommand<O, I> {
+    fn new(subcommand: &'static str) -> Self {
+        Self {
+            subcommand,
+            program: None,
+            args: Vec::new(),
+            current_dir: None,
+            envs: Vec::new(),
+            _m: PhantomData,
+        }
+    }
+
+    /// Sets the `cargo-plumbing` binary to run, instead of discovering it.
+    pub fn program(mut self, program: impl Into<PathBuf>) -> Self {
+        self.program = Some(program.into());
+        self
+    }
+
+    /// Sets the working directory of the command.
+    pub fn current_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Sets an environment variable for the command.
+    pub fn env(mut self, key: impl AsRef<OsStr>, value: impl AsRef<OsStr>) -> Self {
+        self.envs
+            .push((key.as_ref().to_owned(), value.as_ref().to_owned()));
+        self
+    }
+
+    /// Adds a raw argument, for flags not covered by the builder.
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    /// Passes `--locked`.
+    pub fn locked(self) -> Self {
+        self.arg("--locked")
+    }
+
+    /// Passes `--offline`.
+    pub fn offline(self) -> Self {
+        self.arg("--offline")
+    }
+
+    /// Passes `--frozen`.
+    pub fn frozen(self) -> Self {
+        self.arg("--frozen")
+    }
+
+    /// Passes a `--config KEY=VALUE` override.
+    pub fn config(self, config: impl AsRef<OsStr>) -> Self {
+        self.arg("--config").arg(config)
+    }
+
+    /// Passes an unstable `-Z FLAG` to Cargo, such as `script` for single-file packages.
+    pub fn unstable(self, flag: impl AsRef<OsStr>) -> Self {
+        self.arg("-Z").arg(flag)
+    }
+
+    fn values(mut self, flag: &str, values: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        for value in values {
+            self = self.arg(flag).arg(value);
+        }
+        self
+    }
+
+    fn command(&self) -> Command {
+        let program = match &self.program {
+            Some(program) => program.as_os_str().to_owned(),
+            None => env::var_os(CARGO_PLUMBING_BIN).unwrap_or_else(|| "cargo-plumbing".into()),
+        };
+
+        let mut cmd = Command::new(program);
+        cmd.arg("plumbing")
+            .arg(self.subcommand)
+            .arg("--message-format-version")
+            .arg(MESSAGE_FORMAT_VERSION.to_string())
+            .args(&self.args);
+        if let Some(dir) = &self.current_dir {
+            cmd.current_dir(dir);
+        }
+        cmd.envs(self.envs.iter().map(|(k, v)| (k, v)));
+        cmd
+    }
+}
+
+impl<O: DeserializeOwned, I> PlumbingCommand<O, I> {
+    /// Runs the command without any input, collecting its output messages.
+    pub fn output(self) -> io::Result<PlumbingOutput<O>> {
+        self.run(None)
+    }
+
+    fn run(self, input: Option<Vec<u8>>) -> io::Result<PlumbingOutput<O>> {
+        let mut cmd = self.command();
+        cmd.stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+        let mut child = cmd.spawn()?;
+
+        let stdin_writer = match (child.stdin.take(), input) {
+            (Some(mut stdin), Some(input)) => Some(thread::spawn(move || stdin.write_all(&input))),
+            _ => None,
+        };
+        let stderr_reader = child.stderr.take().map(|mut stderr| {
+            thread::spawn(move || {
+                let mut buf = String::new();
+                stderr.read_to_string(&mut buf).map(|_| buf)
+            })
+        });
+
+        // Read all of stdout before parsing, so the command can't block on a full pipe, and
+        // always wait for it and join the threads, so nothing is leaked when something fails.
+        let mut stdout = Vec::new();
+        let read = child
+            .stdout
+            .take()
+            .expect("stdout is piped")
+            .read_to_end(&mut stdout);
+        let status = child.wait();
+        let stderr = match stderr_reader {
+            Some(reader) => reader.join().expect("stderr reader panicked"),
+            None => Ok(String::new()),
+        };
+        let written = match stdin_writer {
+            Some(writer) => writer.join().expect("stdin writer panicked"),
+            None => Ok(()),
+        };
+
+        let status = status?;
+        read?;
+        let stderr = stderr?;
+        // A failing command may exit before reading all of its input. The broken pipe is not
+        // the interesting error then, the exit status and stderr are.
+        if status.success() {
+            written?;
+        }
+        let messages = parse_messages(&stdout, status.success())?;
+
+        Ok(PlumbingOutput {
+            subcommand: self.subcommand,
+            status,
+            messages,
+            stderr,
+        })
+    }
+}
+
+/// Parses the output of a command run with `--message-format-version`, checking and skipping the
+/// leading `schema-version` message.
+///
+/// A command that failed may not have printed anything, so the `schema-version` message is only
+/// required from one that succeeded.
+fn parse_messages<O: DeserializeOwned>(stdout: &[u8], success: bool) -> io::Result<Vec<O>> {
+    let mut lines = stdout.lines();
+    match lines.next().transpose()? {
+        Some(line) => {
+            let SchemaVersion { version } = parse_line(&line)?;
+            if version != MESSAGE_FORMAT_VERSION {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "expected message format version {MESSAGE_FORMAT_VERSION}, got {version}"
+                    ),
+                ));
+            }
+        }
+        None if success => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no `schema-version` message was printed",
+            ));
+        }
+        None => {}
+    }
+
+    lines.map(|line| parse_line(&line?)).collect()
+}
+
+fn parse_line<M: DeserializeOwned>(line: &str) -> io::Result<M> {
+    serde_json::from_str(line).map_err(|err| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("failed to parse message `{line}`: {err}"),
+        )
+    })
+}
+
+impl<O: DeserializeOwned, I: Serialize> PlumbingCommand<O, I> {
+    /// Runs the command with `input` piped to its stdin, collecting its output messages.
+    pub fn output_with_input(
+        self,
+        input: impl IntoIterator<Item = I>,
+    ) -> io::Result<PlumbingOutput<O>> {
+        let mut buf = Vec::new();
+        for message in input {
+            serde_json::to_writer(&mut buf, &message)?;
+            buf.push(b'\n');
+        }
+        self.run(Some(buf))
+    }
+}
+
+impl PlumbingCommand<LocateManifestOut> {
+    /// Creates a `cargo-plumbing locate-manifest` command.
+    pub fn locate_manifest() -> Self {
+        Self::new("locate-manifest")
+    }
+
+    /// Sets the path to start searching for the manifest from.
+    pub fn manifest_path(self, path: impl AsRef<Path>) -> Self {
+        self.arg("--manifest-path").arg(path.as_ref())
+    }
+
+    /// Also locates the root manifest of the workspace.
+    pub fn workspace(self) -> Self {
+        self.arg("--workspace")
+    }
+}
+
+impl PlumbingCommand<ReadManifestOut, ReadManifestIn> {
+    /// Creates a `cargo-plumbing read-manifest` command.
+    pub fn read_manifest(manifest_path: impl AsRef<Path>) -> Self {
+        Self::new("read-manifest")
+            .arg("--manifest-path")
+            .arg(manifest_path.as_ref())
+    }
+
+    /// Reads all manifests in the workspace.
+    pub fn workspace(self) -> Self {
+        self.arg("--workspace")
+    }
+
+    /// Includes the manifests as written, before workspace inheritance and target inference.
+    pub fn original(self) -> Self {
+        self.arg("--original")
+    }
+
+    /// Includes where each table, key and array element is written in the manifests.
+    pub fn spans(self) -> Self {
+        self.arg("--spans")
+    }
+
+    /// Includes the targets of each package, including the ones discovered from its files.
+    pub fn targets(self) -> Self {
+        self.arg("--targets")
+    }
+
+    /// Reports the warnings about each manifest, such as unused keys.
+    pub fn report_warnings(self) -> Self {
+        self.arg("--report-warnings")
+    }
+
+    /// Reports the members of the workspace and why each of them is one.
+    ///
+    /// Only used along with [`Self::workspace`].
+    pub fn report_workspace(self) -> Self {
+        self.arg("--report-workspace")
+    }
+
+    /// Reads the manifests of the locked packages given as input instead, leaving out the
+    /// workspace members.
+    pub fn dependencies(self) -> Self {
+        self.arg("--dependencies")
+    }
+}
+
+impl PlumbingCommand<ReadLockfileOut> {
+    /// Creates a `cargo-plumbing read-lockfile` command.
+    pub fn read_lockfile(lockfile_path: impl AsRef<Path>) -> Self {
+        Self::new("read-lockfile")
+            .arg("--lockfile-path")
+            .arg(lockfile_path.as_ref())
+    }
+
+    /// Creates a `cargo-plumbing read-lockfile` command reading the lockfile of the workspace at
+    /// `manifest_path`, wherever it is configured to be.
+    pub fn read_workspace_lockfile(manifest_path: impl AsRef<Path>) -> Self {
+        Self::new("read-lockfile")
+            .arg("--manifest-path")
+            .arg(manifest_path.as_ref())
+    }
+
+    /// Reports the sources of locked packages replaced by `[source]` tables.
+    pub fn report_source_replacements(self) -> Self {
+        self.arg("--report-source-replacements")
+    }
+}
+
+impl PlumbingCommand<(), WriteLockfileIn> {
+    /// Creates a `cargo-plumbing write-lockfile` command.
+    pub fn write_lockfile(lockfile_path: impl AsRef<Path>) -> Self {
+        Self::new("write-lockfile")
+            .arg("--lockfile-path")
+            .arg(lockfile_path.as_ref())
+    }
+
+    /// Creates a `cargo-plumbing write-lockfile` command writing the lockfile of the workspace at
+    /// `manifest_path`, wherever it is configured to be.
+    pub fn write_workspace_lockfile(manifest_path: impl AsRef<Path>) -> Self {
+        Self::new("write-lockfile")
+            .arg("--manifest-path")
+            .arg(manifest_path.as_ref())
+    }
+}
+
+impl PlumbingCommand<LockDependenciesOut, LockDependenciesIn> {
+    /// Creates a `cargo-plumbing lock-dependencies` command.
+    pub fn lock_dependencies() -> Self {
+        Self::new("lock-dependencies")
+    }
+
+    /// Sets the path to the workspace manifest.
+    pub fn manifest_path(self, path: impl AsRef<Path>) -> Self {
+        self.arg("--manifest-path").arg(path.as_ref())
+    }
+
+    /// Activates the given features.
+    pub fn features(self, features: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        self.values("--features", features)
+    }
+
+    /// Activates all available features.
+    pub fn all_features(self) -> Self {
+        self.arg("--all-features")
+    }
+
+    /// Doesn't activate the `default` feature.
+    pub fn no_default_features(self) -> Self {
+        self.arg("--no-default-features")
+    }
+
+    /// Doesn't resolve dev-dependencies.
+    pub fn no_dev_deps(self) -> Self {
+        self.arg("--no-dev-deps")
+    }
+
+    /// Sets how to pick dependencies requiring a newer Rust, `allow` or `fallback`, and reports
+    /// the locked packages affected by the workspace's `rust-version`.
+    pub fn incompatible_rust_versions(self, mode: impl AsRef<OsStr>) -> Self {
+        self.arg("--incompatible-rust-versions").arg(mode)
+    }
+
+    /// Falls back to dependencies supporting `rust_version` instead of the workspace's
+    /// `rust-version`, and reports the locked packages affected by it.
+    pub fn rust_version(self, rust_version: impl AsRef<OsStr>) -> Self {
+        self.arg("--rust-version").arg(rust_version)
+    }
+
+    /// Resolves to the lowest versions allowed by the requirements, on the nightly channel only.
+    pub fn minimal_versions(self) -> Self {
+        self.arg("--minimal-versions")
+    }
+
+    /// Resolves direct dependencies to the lowest versions allowed by the requirements, on the
+    /// nightly channel only.
+    pub fn direct_minimal_versions(self) -> Self {
+        self.arg("--direct-minimal-versions")
+    }
+
+    /// Reports the locked packages yanked from their registry.
+    pub fn report_yanked(self) -> Self {
+        self.arg("--report-yanked")
+    }
+
+    /// Reports the sources of locked packages replaced by `[source]` tables.
+    pub fn report_source_replacements(self) -> Self {
+        self.arg("--report-source-replacements")
+    }
+
+    /// Reports the locked packages overridden by `[patch]` or `[replace]` tables.
+    pub fn report_patches(self) -> Self {
+        self.arg("--report-patches")
+    }
+}
+
+impl PlumbingCommand<ResolveFeaturesOut, ResolveFeaturesIn> {
+    /// Creates a `cargo-plumbing resolve-features` command.
+    pub fn resolve_features() -> Self {
+        Self::new("resolve-features")
+    }
+
+    /// Sets the path to the workspace manifest.
+    pub fn manifest_path(self, path: impl AsRef<Path>) -> Self {
+        self.arg("--manifest-path").arg(path.as_ref())
+    }
+
+    /// Activates the given features.
+    pub fn features(self, features: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        self.values("--features", features)
+    }
+
+    /// Activates all available features.
+    pub fn all_features(self) -> Self {
+        self.arg("--all-features")
+    }
+
+    /// Doesn't activate the `default` feature.
+    pub fn no_default_features(self) -> Self {
+        self.arg("--no-default-features")
+    }
+
+    /// Resolves for the given target triples.
+    pub fn target(self, targets: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        self.values("--target", targets)
+    }
+
+    /// Includes all binaries.
+    pub fn bins(self) -> Self {
+        self.arg("--bins")
+    }
+
+    /// Includes only the given binaries.
+    pub fn bin(self, names: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        self.values("--bin", names)
+    }
+
+    /// Includes all examples.
+    pub fn examples(self) -> Self {
+        self.arg("--examples")
+    }
+
+    /// Includes only the given examples.
+    pub fn example(self, names: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        self.values("--example", names)
+    }
+
+    /// Includes all tests.
+    pub fn tests(self) -> Self {
+        self.arg("--tests")
+    }
+
+    /// Includes only the given tests.
+    pub fn test(self, names: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        self.values("--test", names)
+    }
+
+    /// Includes all benches.
+    pub fn benches(self) -> Self {
+        self.arg("--benches")
+    }
+
+    /// Includes only the given benches.
+    pub fn bench(self, names: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        self.values("--bench", names)
+    }
+
+    /// Includes all targets.
+    pub fn all_targets(self) -> Self {
+        self.arg("--all-targets")
+    }
+}
+
+impl PlumbingCommand<PlanBuildOut, PlanBuildIn> {
+    /// Creates a `cargo-plumbing plan-build` command.
+    pub fn plan_build() -> Self {
+        Self::new("plan-build")
+    }
+
+    /// Sets the path to the workspace manifest.
+    pub fn manifest_path(self, path: impl AsRef<Path>) -> Self {
+        self.arg("--manifest-path").arg(path.as_ref())
+    }
+
+    /// Plans for the given target triples.
+    pub fn target(self, targets: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        self.values("--target", targets)
+    }
+
+    /// Sets the profile of the unit graph.
+    pub fn profile(self, profile: impl AsRef<OsStr>) -> Self {
+        self.arg("--profile").arg(profile)
+    }
+}
+
+impl PlumbingCommand<QueryIndexOut> {
+    /// Creates a `cargo-plumbing query-index` command for the given packages, each as `NAME` or
+    /// `NAME@VERSION_REQ`.
+    pub fn query_index(packages: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Self {
+        let mut cmd = Self::new("query-index");
+        for package in packages {
+            cmd = cmd.arg(package);
+        }
+        cmd
+    }
+
+    /// Queries the registry with the given name instead of crates.io.
+    pub fn registry(self, registry: impl AsRef<OsStr>) -> Self {
+        self.arg("--registry").arg(registry)
+    }
+}
+
+impl PlumbingCommand<VendorOut, VendorIn> {
+    /// Creates a `cargo-plumbing vendor` command copying packages into `path`.
+    pub fn vendor(path: impl AsRef<Path>) -> Self {
+        Self::new("vendor").arg(path.as_ref())
+    }
+
+    /// Sets the path to the workspace manifest.
+    pub fn manifest_path(self, path: impl AsRef<Path>) -> Self {
+        self.arg("--manifest-path").arg(path.as_ref())
+    }
+}
+
+/// The result of running a [`PlumbingCommand`]
+pub struct PlumbingOutput<O> {
+    subcommand: &'static str,
+    /// The exit status of the command.
+    pub status: ExitStatus,
+    /// The output messages, in the order they were printed, without the leading
+    /// `schema-version` message.
+    pub messages: Vec<O>,
+    /// The captured stderr of the command.
+    pub stderr: String,
+}
+
+impl<O> PlumbingOutput<O> {
+    /// Returns the output messages if the command succeeded, or an error with its stderr.
+    pub fn into_messages(self) -> io::Result<Vec<O>> {
+        if self.status.success() {
+            Ok(self.messages)
+        } else {
+            Err(io::Error::other(format!(
+                "`cargo-plumbing {}` failed ({}):\n{}",
+                self.subcommand, self.status, self.stderr
+            )))
+        }
+    }
+}
@@ -7,21 +7,73 @@
 //! This library is intended to be used by tools that invoke `cargo-plumbing` commands and need
 //! to parse the jsonlines output. The [`MessageIter`] provides a convenient way to handle the
 //! jsonlines stream.
+//!
+//! With the `tokio` feature, [`MessageStream`] is the asynchronous counterpart of [`MessageIter`],
+//! created by the `parse_stream_async` function of each message enum.
+//!
+//! With the `client` feature, [`client::PlumbingCommand`] takes care of spawning the commands
+//! themselves.
+//!
+//! ## Versioning
+//!
+//! The message format has a version, [`MESSAGE_FORMAT_VERSION`]. When a command is passed
+//! `--message-format-version <VERSION>`, it fails unless it supports `VERSION`, and otherwise
+//! prints a `schema-version` message (see [`SchemaVersion`]) before any other output. Tools
+//! should pass the version they were built against so an incompatible `cargo-plumbing` binary is
+//! rejected up front instead of producing messages the tool can't parse.
+//!
+//! Input streams may also start with a `schema-version` message. Commands reject input whose
+//! version they don't support.
+//!
+//! The version is bumped on breaking changes to any message:
+//!
+//! - Removing or renaming a message or field
+//! - Changing the type or meaning of a field
+//! - Making an optional field required
+//! - Printing a new message without it being requested
+//!
+//! The following are compatible changes and keep the version:
+//!
+//! - Adding an optional field. Consumers must ignore fields they don't know.
+//! - Adding a message that is only printed when requested through a new flag, or right before
+//!   the command fails
 
 use std::io::{self, BufRead};
 use std::marker::PhantomData;
 
 use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 
+#[cfg(feature = "client")]
+pub mod client;
 pub mod locate_manifest;
 pub mod lock_dependencies;
 pub mod lockfile;
 pub mod plan_build;
+pub mod query_index;
 pub mod read_lockfile;
 pub mod read_manifest;
 pub mod resolve_features;
+pub mod validate;
+pub mod vendor;
 pub mod write_lockfile;
 
+/// The version of the message format described by this library
+///
+/// See the [crate-level documentation](crate#versioning) for the compatibility rules.
+pub const MESSAGE_FORMAT_VERSION: u32 = 1;
+
+/// The `schema-version` message printed first when `--message-format-version` is passed
+///
+/// Every message enum has a matching `SchemaVersion` variant, so the message can be parsed as part
+/// of any command's output and be passed along as input.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "reason", rename = "schema-version")]
+pub struct SchemaVersion {
+    /// The message format version.
+    pub version: u32,
+}
+
 /// Iterator over deserialized jsonline messages
 ///
 /// It is not intended to be constructed directly by users. Instead, users should use solutions
@@ -60,3 +112,123 @@ impl<R: BufRead, M: DeserializeOwned> Iterator for MessageIter<R, M> {
             .transpose()
     }
 }
+
+/// Asynchronous stream over deserialized jsonline messages
+///
+/// This is the [`Stream`] counterpart of [`MessageIter`], reading from a [`tokio::io::AsyncBufRead`].
+/// Like [`MessageIter`], it ends on the first line that fails to deserialize into `M`. Users
+/// should use solutions provided by the messages enums, such as
+/// [`LocateManifestOut::parse_stream_async`] and other messages.
+///
+/// # Type Parameters
+///
+/// - `R`: The buffered async reader it reads from.
+/// - `M`: The message type it deserializes each jsonline into.
+///
+/// [`Stream`]: futures_core::Stream
+/// [`LocateManifestOut::parse_stream_async`]: locate_manifest::LocateManifestOut::parse_stream_async
+#[cfg(feature = "tokio")]
+pub struct MessageStream<R, M> {
+    input: R,
+    line: Vec<u8>,
+    done: bool,
+    _m: PhantomData<fn() -> M>,
+}
+
+#[cfg(feature = "tokio")]
+impl<R, M> MessageStream<R, M> {
+    pub(crate) fn new(input: R) -> Self {
+        Self {
+            input,
+            line: Vec::new(),
+            done: false,
+            _m: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R: tokio::io::AsyncBufRead + Unpin, M: DeserializeOwned> futures_core::Stream
+    for MessageStream<R, M>
+{
+    type Item = io::Result<M>;
+
+    fn poll_next(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        use std::pin::Pin;
+        use std::task::{ready, Poll};
+
+        let this = self.get_mut();
+        if this.done {
+            return Poll::Ready(None);
+        }
+
+        // The partial line is kept in `self.line` so reading can resume after `Poll::Pending`.
+        loop {
+            let available = match ready!(Pin::new(&mut this.input).poll_fill_buf(cx)) {
+                Ok(available) => available,
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            };
+            if available.is_empty() {
+                if this.line.is_empty() {
+                    this.done = true;
+                    return Poll::Ready(None);
+                }
+                break;
+            }
+            if let Some(i) = available.iter().position(|&b| b == b'\n') {
+                this.line.extend_from_slice(&available[..i]);
+                Pin::new(&mut this.input).consume(i + 1);
+                break;
+            }
+            let n = available.len();
+            this.line.extend_from_slice(available);
+            Pin::new(&mut this.input).consume(n);
+        }
+
+        let line = std::mem::take(&mut this.line);
+        let Ok(line) = String::from_utf8(line) else {
+            return Poll::Ready(Some(Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            ))));
+        };
+        let mut deserializer = serde_json::Deserializer::from_str(&line);
+        match M::deserialize(&mut deserializer) {
+            Ok(message) => Poll::Ready(Some(Ok(message))),
+            Err(_) => {
+                this.done = true;
+                Poll::Ready(None)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn parse_stream_async() {
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    use futures_core::Stream;
+
+    use crate::locate_manifest::LocateManifestOut;
+
+    let input = b"{\"reason\":\"manifest-location\",\"manifest_path\":\"/a/Cargo.toml\"}
+{\"reason\":\"manifest-location\",\"manifest_path\":\"/b/Cargo.toml\"}
+{\"reason\":\"unknown\"}
+{\"reason\":\"manifest-location\",\"manifest_path\":\"/c/Cargo.toml\"}
+";
+    let mut stream = pin!(LocateManifestOut::parse_stream_async(&input[..]));
+    let mut cx = Context::from_waker(Waker::noop());
+
+    let mut paths = Vec::new();
+    while let Poll::Ready(Some(message)) = stream.as_mut().poll_next(&mut cx) {
+        if let LocateManifestOut::ManifestLocation { manifest_path } = message.unwrap() {
+            paths.push(manifest_path);
+        }
+    }
+    assert_eq!(paths, ["/a/Cargo.toml", "/b/Cargo.toml"]);
+}
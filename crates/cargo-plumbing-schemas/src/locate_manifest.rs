@@ -12,12 +12,37 @@ use crate::MessageIter;
 #[serde(tag = "reason", rename_all = "kebab-case")]
 #[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
 pub enum LocateManifestOut {
-    /// A message containing the location of a found `Cargo.toml` manifest.
+    /// The message format version, printed first when `--message-format-version` is passed.
+    SchemaVersion { version: u32 },
+    /// The nearest `Cargo.toml` manifest, which may be a package or a virtual manifest.
     ManifestLocation {
         /// The absolute path to the manifest file.
         #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
         manifest_path: Utf8PathBuf,
     },
+    /// A manifest walked while searching for the workspace root, when `--workspace` is passed
+    ///
+    /// Printed nearest first, starting with the nearest manifest and ending with the workspace
+    /// root when it is one of its ancestors.
+    ManifestCandidate {
+        /// The absolute path to the manifest file.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        manifest_path: Utf8PathBuf,
+    },
+    /// The root manifest of the enclosing workspace, when `--workspace` is passed
+    ///
+    /// This is the nearest manifest itself when it isn't part of another workspace.
+    WorkspaceRoot {
+        /// The absolute path to the manifest file.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        manifest_path: Utf8PathBuf,
+        /// The absolute path to the lockfile of the workspace.
+        ///
+        /// Single-file packages keep their lockfile along with their build output instead of
+        /// next to the manifest.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        lockfile_path: Utf8PathBuf,
+    },
 }
 
 impl LocateManifestOut {
@@ -28,6 +53,14 @@ impl LocateManifestOut {
             _m: PhantomData::<Self>,
         }
     }
+
+    /// Creates a stream to asynchronously parse a stream of [`LocateManifestOut`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
 }
 
 #[cfg(feature = "unstable-schema")]
@@ -1,7 +1,9 @@
 //! Messages used by `cargo plumbing lock-dependencies` command
 
+use std::path::PathBuf;
 use std::{io::Read, marker::PhantomData};
 
+use cargo_util_schemas::core::PackageIdSpec;
 use serde::{Deserialize, Serialize};
 
 use crate::lockfile::{NormalizedDependency, NormalizedPatch};
@@ -13,6 +15,10 @@ use crate::MessageIter;
 #[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
 #[allow(clippy::large_enum_variant)]
 pub enum LockDependenciesIn {
+    /// The message format version the input was produced with.
+    SchemaVersion {
+        version: u32,
+    },
     /// The locked package from the lockfile
     ///
     /// Expected to be inputted in a lexicographical order based on the package
@@ -35,6 +41,14 @@ impl LockDependenciesIn {
             _m: PhantomData::<Self>,
         }
     }
+
+    /// Creates a stream to asynchronously parse a stream of [`LockDependenciesIn`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
 }
 
 /// Output messages for `cargo-plumbing lock-dependencies`.
@@ -43,8 +57,16 @@ impl LockDependenciesIn {
 #[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
 #[allow(clippy::large_enum_variant)]
 pub enum LockDependenciesOut {
+    /// The message format version, printed first when `--message-format-version` is passed.
+    SchemaVersion {
+        version: u32,
+    },
     Lockfile {
         version: u32,
+        /// Whether only the selected features or no dev-dependencies were resolved, unlike a
+        /// full `Cargo.lock`.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        partial: bool,
     },
     /// The locked package from the lockfile
     ///
@@ -58,6 +80,165 @@ pub enum LockDependenciesOut {
     UnusedPatches {
         unused: NormalizedPatch,
     },
+    /// A newer version allowed for a locked package requires a newer Rust than the honored
+    /// `rust-version`
+    ///
+    /// Printed after the locked packages when the resolver falls back to versions supporting the
+    /// `rust-version`, with `--incompatible-rust-versions fallback` or `--rust-version`. Packages
+    /// kept at their version in the input aren't reported.
+    RustVersionSkipped {
+        /// The locked package.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        id: PackageIdSpec,
+        /// The newest version requiring a newer Rust.
+        version: String,
+        /// The `rust-version` of `version`.
+        rust_version: String,
+    },
+    /// A locked package requires a newer Rust than the honored `rust-version`
+    ///
+    /// Printed after the locked packages when `--incompatible-rust-versions` or `--rust-version`
+    /// is passed.
+    RustVersionIncompatible {
+        /// The locked package.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        id: PackageIdSpec,
+        /// The `rust-version` of the locked package.
+        rust_version: String,
+        /// The newest version allowed by the dependents supporting the `rust-version`, if any
+        /// exists.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        compatible_version: Option<String>,
+    },
+    /// A locked package was yanked from its registry
+    ///
+    /// Printed after the locked packages when `--report-yanked` is passed.
+    Yanked {
+        /// The locked package.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        id: PackageIdSpec,
+    },
+    /// The locked packages from `source` are fetched from `replace_with` instead, as configured
+    /// by `[source]` tables
+    ///
+    /// Printed after the locked packages when `--report-source-replacements` is passed, once for
+    /// each replaced source.
+    SourceReplacement {
+        /// The source in the package ID specifications of the locked packages.
+        source: String,
+        /// The source the packages are fetched from.
+        replace_with: String,
+    },
+    /// A locked package was patched in by a `[patch]` table
+    ///
+    /// Printed after the locked packages when `--report-patches` is passed, once for each patch
+    /// the resolve used. Unused patches are listed by `unused-patches` instead.
+    Patch {
+        /// The source the patch applies to, such as the crates.io index.
+        source: String,
+        /// The locked package the patch brought in.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        id: PackageIdSpec,
+        /// The source the patched-in package comes from.
+        patch_source: String,
+        /// The dependencies on `source` resolved to the patched-in package.
+        dependencies: Vec<OverriddenDependency>,
+        /// The manifest declaring the patch, absent when it is declared in the Cargo
+        /// configuration.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        manifest_path: Option<PathBuf>,
+    },
+    /// A locked package is replaced by another one through the `[replace]` table
+    ///
+    /// Printed after the locked packages when `--report-patches` is passed.
+    Replace {
+        /// The locked package being replaced.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        id: PackageIdSpec,
+        /// The package used in its place.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        replace_with: PackageIdSpec,
+        /// The dependencies resolved to the replaced package.
+        dependencies: Vec<OverriddenDependency>,
+        /// The manifest declaring the replacement.
+        manifest_path: PathBuf,
+    },
+    /// The dependencies couldn't be resolved
+    ///
+    /// Printed last, right before the command fails.
+    ResolveError {
+        /// The human-readable description of the conflict.
+        message: String,
+        /// The packages from the one whose dependencies couldn't be resolved up to the workspace
+        /// member requiring it.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "Vec<String>"))]
+        package_path: Vec<PackageIdSpec>,
+        /// The requirements that couldn't be satisfied, among the dependencies of the first
+        /// package of `package_path`, along with the requirements they collide with.
+        ///
+        /// Empty when the failing requirements couldn't be determined.
+        #[serde(default)]
+        requirements: Vec<ResolveRequirement>,
+        /// The versions of the packages in `requirements` and why each was rejected.
+        #[serde(default)]
+        candidates: Vec<ResolveCandidate>,
+        /// Whether the resolve ran offline, where only downloaded packages are candidates.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        offline: bool,
+    },
+}
+
+/// A dependency resolved to a package overridden by `[patch]` or `[replace]`
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub struct OverriddenDependency {
+    /// The package declaring the dependency.
+    #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+    pub package: PackageIdSpec,
+    /// The version requirement of the dependency, as declared.
+    pub req: String,
+}
+
+/// A dependency requirement taking part in a resolve failure
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub struct ResolveRequirement {
+    /// The name of the required package.
+    pub name: String,
+    /// The version requirement, as declared.
+    pub req: String,
+    /// The package declaring the requirement.
+    #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+    pub package: PackageIdSpec,
+}
+
+/// A version considered for the requirements of a resolve failure
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub struct ResolveCandidate {
+    /// The package at this version.
+    #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+    pub id: PackageIdSpec,
+    /// Why the version couldn't be used, absent if it could be.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rejected: Option<RejectedReason>,
+}
+
+/// Why a version couldn't be used to satisfy a requirement
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub enum RejectedReason {
+    /// It doesn't match every requirement on the package.
+    Unmatched,
+    /// It was yanked from its registry.
+    Yanked,
+    /// It hasn't been downloaded and the resolve ran offline.
+    Offline,
+    /// Its index entry needs a newer version of Cargo.
+    Unsupported,
+    /// It needs a newer Rust than the workspace's `rust-version`, which the resolver honors.
+    RustVersion,
 }
 
 impl LockDependenciesOut {
@@ -68,6 +249,14 @@ impl LockDependenciesOut {
             _m: PhantomData::<Self>,
         }
     }
+
+    /// Creates a stream to asynchronously parse a stream of [`LockDependenciesOut`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
 }
 
 #[cfg(feature = "unstable-schema")]
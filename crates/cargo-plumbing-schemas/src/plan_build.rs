@@ -17,6 +17,10 @@ use crate::MessageIter;
 #[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
 #[allow(clippy::large_enum_variant)]
 pub enum PlanBuildIn {
+    /// The message format version the input was produced with.
+    SchemaVersion {
+        version: u32,
+    },
     Manifest {
         #[serde(default, skip_serializing_if = "std::ops::Not::not")]
         workspace: bool,
@@ -63,6 +67,14 @@ impl PlanBuildIn {
             _m: PhantomData::<Self>,
         }
     }
+
+    /// Creates a stream to asynchronously parse a stream of [`PlanBuildIn`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
 }
 
 /// Output messages for `cargo-plumbing plan-build`.
@@ -70,6 +82,8 @@ impl PlanBuildIn {
 #[serde(tag = "reason", rename_all = "kebab-case")]
 #[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
 pub enum PlanBuildOut {
+    /// The message format version, printed first when `--message-format-version` is passed.
+    SchemaVersion { version: u32 },
     Unit {
         id: usize,
         target: UnitTarget,
@@ -131,6 +145,14 @@ impl PlanBuildOut {
             _m: PhantomData::<Self>,
         }
     }
+
+    /// Creates a stream to asynchronously parse a stream of [`PlanBuildOut`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
 }
 
 #[cfg(feature = "unstable-schema")]
@@ -0,0 +1,89 @@
+//! Messages used by `cargo plumbing query-index` command
+
+use std::collections::BTreeMap;
+use std::{io::Read, marker::PhantomData};
+
+use cargo_util_schemas::core::PackageIdSpec;
+use serde::{Deserialize, Serialize};
+
+use crate::MessageIter;
+
+/// Output messages for `cargo-plumbing query-index`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub enum QueryIndexOut {
+    /// The message format version, printed first when `--message-format-version` is passed.
+    SchemaVersion { version: u32 },
+    /// A version of a queried package published to the registry
+    ///
+    /// Printed for each version matching the requirement, oldest first.
+    IndexEntry {
+        /// The package ID specification of this version.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        id: PackageIdSpec,
+        /// The features and the features or dependencies each enables.
+        features: BTreeMap<String, Vec<String>>,
+        /// The dependencies of this version.
+        dependencies: Vec<IndexDependency>,
+        /// The minimum supported Rust version.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        rust_version: Option<String>,
+        /// Whether this version was yanked.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        yanked: bool,
+        /// The checksum of the `.crate` file.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        checksum: Option<String>,
+    },
+}
+
+/// A dependency as declared in the registry index
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub struct IndexDependency {
+    /// The name the dependency is referred to by, which may be a rename.
+    pub name: String,
+    /// The name of the depended-on package, if it is renamed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package: Option<String>,
+    /// The version requirement.
+    pub req: String,
+    /// `dev` or `build`, absent for normal dependencies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+    pub default_features: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+    /// The `cfg` expression or target triple the dependency is limited to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+impl QueryIndexOut {
+    /// Creates an iterator to parse a stream of [`QueryIndexOut`]s.
+    pub fn parse_stream<R: Read>(input: R) -> MessageIter<R, Self> {
+        MessageIter {
+            input,
+            _m: PhantomData::<Self>,
+        }
+    }
+
+    /// Creates a stream to asynchronously parse a stream of [`QueryIndexOut`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
+}
+
+#[cfg(feature = "unstable-schema")]
+#[test]
+fn dump_query_index_schema() {
+    let schema = schemars::schema_for!(QueryIndexOut);
+    let dump = serde_json::to_string_pretty(&schema).unwrap();
+    snapbox::assert_data_eq!(dump, snapbox::file!("../query-index.out.schema.json").raw());
+}
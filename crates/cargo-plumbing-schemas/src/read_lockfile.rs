@@ -14,6 +14,10 @@ use crate::MessageIter;
 #[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
 #[allow(clippy::large_enum_variant)]
 pub enum ReadLockfileOut {
+    /// The message format version, printed first when `--message-format-version` is passed.
+    SchemaVersion {
+        version: u32,
+    },
     Lockfile {
         version: u32,
     },
@@ -26,6 +30,17 @@ pub enum ReadLockfileOut {
         #[serde(flatten)]
         package: NormalizedDependency,
     },
+    /// The locked packages from `source` are fetched from `replace_with` instead, as configured
+    /// by `[source]` tables
+    ///
+    /// Printed after the locked packages when `--report-source-replacements` is passed, once for
+    /// each replaced source.
+    SourceReplacement {
+        /// The source in the package ID specifications of the locked packages.
+        source: String,
+        /// The source the packages are fetched from.
+        replace_with: String,
+    },
     UnusedPatches {
         unused: NormalizedPatch,
     },
@@ -39,6 +54,14 @@ impl ReadLockfileOut {
             _m: PhantomData::<Self>,
         }
     }
+
+    /// Creates a stream to asynchronously parse a stream of [`ReadLockfileOut`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
 }
 
 #[cfg(feature = "unstable-schema")]
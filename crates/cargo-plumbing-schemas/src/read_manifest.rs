@@ -1,5 +1,7 @@
 //! Messages used by `cargo plumbing read-manifest` command
 
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::{io::Read, marker::PhantomData};
 
 use camino::Utf8PathBuf;
@@ -7,13 +9,54 @@ use cargo_util_schemas::core::PackageIdSpec;
 pub use cargo_util_schemas::manifest::TomlManifest;
 use serde::{Deserialize, Serialize};
 
+use crate::lockfile::{NormalizedDependency, NormalizedPatch};
 use crate::MessageIter;
 
+/// Input messages for `cargo-plumbing read-manifest`, read with `--dependencies`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+#[allow(clippy::large_enum_variant)]
+pub enum ReadManifestIn {
+    /// The message format version the input was produced with.
+    SchemaVersion {
+        version: u32,
+    },
+    /// The locked package whose manifest to read
+    LockedPackage {
+        #[serde(flatten)]
+        package: NormalizedDependency,
+    },
+    UnusedPatches {
+        unused: NormalizedPatch,
+    },
+}
+
+impl ReadManifestIn {
+    /// Creates an iterator to parse a stream of [`ReadManifestIn`]s.
+    pub fn parse_stream<R: Read>(input: R) -> MessageIter<R, Self> {
+        MessageIter {
+            input,
+            _m: PhantomData::<Self>,
+        }
+    }
+
+    /// Creates a stream to asynchronously parse a stream of [`ReadManifestIn`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
+}
+
 /// Output messages for `cargo-plumbing read-manifest`.
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "reason", rename_all = "kebab-case")]
 #[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
 pub enum ReadManifestOut {
+    /// The message format version, printed first when `--message-format-version` is passed.
+    SchemaVersion { version: u32 },
     /// A message containing the contents of a parsed `Cargo.toml` manifest.
     Manifest {
         #[serde(default, skip_serializing_if = "std::ops::Not::not")]
@@ -21,6 +64,9 @@ pub enum ReadManifestOut {
         /// The path to the manifest file that was read.
         #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
         path: Utf8PathBuf,
+        /// Whether the manifest is embedded in a single-file package, read with `-Zscript`.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        embedded: bool,
         /// The package ID specification.
         ///
         /// This command also outputs virtual manifests and virtual manifests don't have
@@ -32,7 +78,142 @@ pub enum ReadManifestOut {
         pkg_id: Option<PackageIdSpec>,
         /// The fully parsed and deserialized manifest content.
         manifest: TomlManifest,
+        /// The manifest as written, before workspace inheritance is resolved and targets are
+        /// inferred.
+        ///
+        /// Only present when `--original` is passed.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        original: Option<TomlManifest>,
+        /// The contents of the manifest file.
+        ///
+        /// Only present when `--original` is passed.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        document: Option<String>,
+        /// Where each table, key and array element is written in the manifest file, by its
+        /// dotted path such as `dependencies.serde.version` or `bin[0].name`.
+        ///
+        /// Only present when `--spans` is passed.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        spans: Option<BTreeMap<String, ManifestSpan>>,
+        /// The targets of the package, including the discovered ones, in the format of the
+        /// `targets` of `cargo metadata`.
+        ///
+        /// Only present for package manifests when `--targets` is passed.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        targets: Option<Vec<ManifestTarget>>,
+    },
+    /// A warning Cargo reports about a manifest, such as an unused key
+    ///
+    /// Printed after the manifest it is about when `--report-warnings` is passed.
+    Warning {
+        /// The path to the manifest file the warning is about.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        path: Utf8PathBuf,
+        /// The human-readable warning.
+        message: String,
+        /// The dotted path of the unused key, for unused key warnings.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+        /// Whether Cargo fails when loading a workspace with this manifest.
+        #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+        critical: bool,
     },
+    /// The members of the workspace and why each of them is one
+    ///
+    /// Printed after the manifests when `--report-workspace` is passed along with `--workspace`.
+    Workspace {
+        /// The path to the workspace manifest.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        path: Utf8PathBuf,
+        /// The members of the workspace.
+        members: Vec<WorkspaceMember>,
+        /// The members selected when no package is given, which are all of them unless
+        /// `default-members` is set.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "Vec<String>"))]
+        default_members: Vec<PackageIdSpec>,
+        /// The `exclude` paths as written.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        exclude: Vec<String>,
+        /// The resolver version of the workspace.
+        resolver: String,
+    },
+}
+
+/// A target of a package
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub struct ManifestTarget {
+    /// The kinds of the target, such as `lib`, `bin`, `example`, `test`, `bench` or
+    /// `custom-build`.
+    pub kind: Vec<String>,
+    /// The crate types the target is compiled to.
+    pub crate_types: Vec<String>,
+    pub name: String,
+    /// The path to the root source file of the target, absent for `metabuild` build scripts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub src_path: Option<PathBuf>,
+    pub edition: String,
+    #[serde(
+        rename = "required-features",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub required_features: Option<Vec<String>>,
+    /// Whether the target is documented by `cargo doc`.
+    pub doc: bool,
+    /// Whether the documentation tests of the target are run by `cargo test`.
+    pub doctest: bool,
+    /// Whether the target is tested by `cargo test`.
+    pub test: bool,
+    /// Whether the target is built with the libtest harness when tested or benchmarked.
+    pub harness: bool,
+}
+
+/// A member of a workspace
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub struct WorkspaceMember {
+    /// The member package.
+    #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+    pub id: PackageIdSpec,
+    /// The path to the manifest file of the member.
+    #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+    pub path: Utf8PathBuf,
+    /// Whether the member is the root package of the workspace.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub root: bool,
+    /// The `members` pattern the member matches.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern: Option<String>,
+    /// The member depending on this one by path, when it isn't the root and matches no pattern.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "unstable-schema", schemars(with = "Option<String>"))]
+    pub path_dependency_of: Option<PackageIdSpec>,
+}
+
+/// Where a table, key or array element is written in a manifest file
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub struct ManifestSpan {
+    /// The span of the key, absent for array elements.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<Span>,
+    /// The span of the value.
+    pub value: Span,
+}
+
+/// A range of a manifest file
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub struct Span {
+    /// The byte offset the range starts at.
+    pub start: usize,
+    /// The byte offset right after the range.
+    pub end: usize,
+    /// The line the range starts at, starting from 1.
+    pub line: usize,
+    /// The column the range starts at in characters, starting from 1.
+    pub column: usize,
 }
 
 impl ReadManifestOut {
@@ -43,6 +224,14 @@ impl ReadManifestOut {
             _m: PhantomData::<Self>,
         }
     }
+
+    /// Creates a stream to asynchronously parse a stream of [`ReadManifestOut`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
 }
 
 #[cfg(feature = "unstable-schema")]
@@ -54,4 +243,11 @@ fn dump_read_manifest_schema() {
         dump,
         snapbox::file!("../read-manifest.out.schema.json").raw()
     );
+
+    let schema = schemars::schema_for!(ReadManifestIn);
+    let dump = serde_json::to_string_pretty(&schema).unwrap();
+    snapbox::assert_data_eq!(
+        dump,
+        snapbox::file!("../read-manifest.in.schema.json").raw()
+    );
 }
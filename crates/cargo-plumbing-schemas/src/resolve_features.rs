@@ -15,6 +15,10 @@ use crate::MessageIter;
 #[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
 #[allow(clippy::large_enum_variant)]
 pub enum ResolveFeaturesIn {
+    /// The message format version the input was produced with.
+    SchemaVersion {
+        version: u32,
+    },
     Manifest {
         #[serde(default, skip_serializing_if = "std::ops::Not::not")]
         workspace: bool,
@@ -50,12 +54,24 @@ impl ResolveFeaturesIn {
             _m: PhantomData::<Self>,
         }
     }
+
+    /// Creates a stream to asynchronously parse a stream of [`ResolveFeaturesIn`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 #[serde(tag = "reason", rename_all = "kebab-case")]
 #[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
 pub enum ResolveFeaturesOut {
+    /// The message format version, printed first when `--message-format-version` is passed.
+    SchemaVersion {
+        version: u32,
+    },
     Activated {
         id: PackageIdSpec,
         #[serde(skip_serializing_if = "String::is_empty")]
@@ -0,0 +1,51 @@
+//! Messages used by `cargo plumbing validate` command
+
+use std::{io::Read, marker::PhantomData};
+
+use serde::{Deserialize, Serialize};
+
+use crate::MessageIter;
+
+/// Output messages for `cargo-plumbing validate`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub enum ValidateOut {
+    /// The message format version, printed first when `--message-format-version` is passed.
+    SchemaVersion { version: u32 },
+    /// A problem found in the validated input.
+    Diagnostic {
+        /// The 1-based line of the offending message, absent for problems with the input as a
+        /// whole.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        line: Option<usize>,
+        /// The description of the problem.
+        message: String,
+    },
+}
+
+impl ValidateOut {
+    /// Creates an iterator to parse a stream of [`ValidateOut`]s.
+    pub fn parse_stream<R: Read>(input: R) -> MessageIter<R, Self> {
+        MessageIter {
+            input,
+            _m: PhantomData::<Self>,
+        }
+    }
+
+    /// Creates a stream to asynchronously parse a stream of [`ValidateOut`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
+}
+
+#[cfg(feature = "unstable-schema")]
+#[test]
+fn dump_validate_schema() {
+    let schema = schemars::schema_for!(ValidateOut);
+    let dump = serde_json::to_string_pretty(&schema).unwrap();
+    snapbox::assert_data_eq!(dump, snapbox::file!("../validate.out.schema.json").raw());
+}
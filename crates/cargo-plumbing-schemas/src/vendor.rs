@@ -0,0 +1,107 @@
+//! Messages used by `cargo plumbing vendor` command
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::{io::Read, marker::PhantomData};
+
+use cargo_util_schemas::core::PackageIdSpec;
+use serde::{Deserialize, Serialize};
+
+use crate::lockfile::{NormalizedDependency, NormalizedPatch};
+use crate::MessageIter;
+
+/// Input messages for `cargo-plumbing vendor`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+#[allow(clippy::large_enum_variant)]
+pub enum VendorIn {
+    /// The message format version the input was produced with.
+    SchemaVersion {
+        version: u32,
+    },
+    /// The locked package to vendor
+    LockedPackage {
+        #[serde(flatten)]
+        package: NormalizedDependency,
+    },
+    UnusedPatches {
+        unused: NormalizedPatch,
+    },
+}
+
+impl VendorIn {
+    /// Creates an iterator to parse a stream of [`VendorIn`]s.
+    pub fn parse_stream<R: Read>(input: R) -> MessageIter<R, Self> {
+        MessageIter {
+            input,
+            _m: PhantomData::<Self>,
+        }
+    }
+
+    /// Creates a stream to asynchronously parse a stream of [`VendorIn`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
+}
+
+/// Output messages for `cargo-plumbing vendor`.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+#[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
+pub enum VendorOut {
+    /// The message format version, printed first when `--message-format-version` is passed.
+    SchemaVersion { version: u32 },
+    /// A package copied into the vendor directory
+    VendoredPackage {
+        /// The vendored package.
+        #[cfg_attr(feature = "unstable-schema", schemars(with = "String"))]
+        id: PackageIdSpec,
+        /// The directory the package was copied to.
+        path: PathBuf,
+    },
+    /// A `[source]` table to add to the Cargo configuration to build from the vendor directory
+    ///
+    /// Printed after the vendored packages: one for each replaced source and one for the vendor
+    /// directory itself.
+    Source {
+        /// The name of the table, as in `[source.<name>]`.
+        name: String,
+        /// The keys of the table, such as `registry`, `git`, `rev`, `replace-with` or
+        /// `directory`.
+        config: BTreeMap<String, String>,
+    },
+}
+
+impl VendorOut {
+    /// Creates an iterator to parse a stream of [`VendorOut`]s.
+    pub fn parse_stream<R: Read>(input: R) -> MessageIter<R, Self> {
+        MessageIter {
+            input,
+            _m: PhantomData::<Self>,
+        }
+    }
+
+    /// Creates a stream to asynchronously parse a stream of [`VendorOut`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
+}
+
+#[cfg(feature = "unstable-schema")]
+#[test]
+fn dump_vendor_schema() {
+    let schema = schemars::schema_for!(VendorOut);
+    let dump = serde_json::to_string_pretty(&schema).unwrap();
+    snapbox::assert_data_eq!(dump, snapbox::file!("../vendor.out.schema.json").raw());
+
+    let schema = schemars::schema_for!(VendorIn);
+    let dump = serde_json::to_string_pretty(&schema).unwrap();
+    snapbox::assert_data_eq!(dump, snapbox::file!("../vendor.in.schema.json").raw());
+}
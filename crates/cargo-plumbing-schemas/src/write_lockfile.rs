@@ -13,6 +13,10 @@ use crate::MessageIter;
 #[cfg_attr(feature = "unstable-schema", derive(schemars::JsonSchema))]
 #[allow(clippy::large_enum_variant)]
 pub enum WriteLockfileIn {
+    /// The message format version the input was produced with.
+    SchemaVersion {
+        version: u32,
+    },
     Lockfile {
         version: Option<u32>,
     },
@@ -38,6 +42,14 @@ impl WriteLockfileIn {
             _m: PhantomData::<Self>,
         }
     }
+
+    /// Creates a stream to asynchronously parse a stream of [`WriteLockfileIn`]s.
+    #[cfg(feature = "tokio")]
+    pub fn parse_stream_async<R: tokio::io::AsyncBufRead>(
+        input: R,
+    ) -> crate::MessageStream<R, Self> {
+        crate::MessageStream::new(input)
+    }
 }
 
 #[cfg(feature = "unstable-schema")]
@@ -1,12 +1,12 @@
-use std::io::{BufReader, Read, Write};
 use std::path::PathBuf;
-use std::process::{Command, Stdio};
 
 use cargo::CargoResult;
+use cargo_plumbing_schemas::client::PlumbingCommand;
 use cargo_plumbing_schemas::locate_manifest::LocateManifestOut;
-use cargo_plumbing_schemas::read_lockfile::ReadLockfileOut;
-use cargo_plumbing_schemas::read_manifest::ReadManifestOut;
+use cargo_plumbing_schemas::write_lockfile::WriteLockfileIn;
 use clap::Parser;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 
 #[derive(Debug, Parser)]
 struct Args {
@@ -46,241 +46,119 @@ struct Args {
 }
 
 fn run(args: &Args) -> CargoResult<()> {
-    let manifest_path = {
-        let mut cmd = cargo_plumbing_bin();
-        cmd.args(["plumbing", "locate-manifest"])
-            .stdout(Stdio::piped());
-
+    let (manifest_path, ws_lockfile_path) = {
+        let mut cmd = PlumbingCommand::locate_manifest().workspace();
         if let Some(manifest_path) = &args.manifest_path {
-            cmd.arg("--manifest-path");
-            cmd.arg(manifest_path);
+            cmd = cmd.manifest_path(manifest_path);
         }
 
-        let mut child = cmd.spawn().expect("failed to run locate-manifest");
-        let stdout = child.stdout.take().expect("failed to get stdout");
-        let messages = LocateManifestOut::parse_stream(BufReader::new(stdout));
-
         let mut manifest_path: Option<camino::Utf8PathBuf> = None;
+        let mut ws_lockfile_path: Option<camino::Utf8PathBuf> = None;
 
-        #[allow(clippy::never_loop)]
-        for message in messages {
-            match message.expect("failed to parse message") {
+        for message in cmd.output()?.into_messages()? {
+            match message {
                 LocateManifestOut::ManifestLocation { manifest_path: m } => {
                     manifest_path = Some(m);
-                    break;
                 }
+                LocateManifestOut::WorkspaceRoot { lockfile_path, .. } => {
+                    ws_lockfile_path = Some(lockfile_path);
+                }
+                LocateManifestOut::SchemaVersion { .. }
+                | LocateManifestOut::ManifestCandidate { .. } => {}
             }
         }
 
-        child.wait().expect("failed to wait for locate-manifest");
-        manifest_path.expect("failed to get manifest_path")
+        (
+            manifest_path.expect("failed to get manifest_path"),
+            ws_lockfile_path.expect("failed to get the lockfile path"),
+        )
     };
 
-    let manifests: Vec<ReadManifestOut> = {
-        let mut cmd = cargo_plumbing_bin();
-        cmd.args(["plumbing", "read-manifest"])
-            .args(["--manifest-path", manifest_path.as_str()])
-            .stdout(Stdio::piped());
-
+    let manifests = {
+        let mut cmd = PlumbingCommand::read_manifest(&manifest_path);
         if args.workspace {
-            cmd.arg("--workspace");
+            cmd = cmd.workspace();
         }
-
-        let mut child = cmd.spawn().expect("failed to run read-manifest");
-        let stdout = child.stdout.take().expect("failed to get stdout");
-        let messages = ReadManifestOut::parse_stream(BufReader::new(stdout));
-
-        child.wait().expect("failed to wait for read-manifest");
-        messages.collect::<Result<Vec<_>, _>>()?
+        cmd.output()?.into_messages()?
     };
 
-    let lockfile_path = args.lockfile_path.clone().unwrap_or_else(|| {
-        let ws_manifest = manifests.iter().find(|m| {
-            matches!(
-                m,
-                ReadManifestOut::Manifest {
-                    workspace: true,
-                    ..
-                }
-            )
-        });
-
-        let ReadManifestOut::Manifest { path, .. } = ws_manifest.unwrap();
-        path.join("Cargo.lock").into()
-    });
-
-    let lockfile = {
-        if lockfile_path.is_file() {
-            let mut cmd = cargo_plumbing_bin();
-            cmd.args(["plumbing", "read-lockfile"])
-                .arg("--lockfile-path")
-                .arg(&lockfile_path)
-                .stdout(Stdio::piped());
-
-            let out = cmd.output().expect("failed to run read-lockfile");
-
-            if out.stdout.is_empty() {
-                None
-            } else {
-                Some(out.stdout)
-            }
-        } else {
-            None
-        }
+    // The lockfile of the workspace may be relocated by config, which `locate-manifest` accounts
+    // for.
+    let lockfile_path = args
+        .lockfile_path
+        .clone()
+        .unwrap_or_else(|| ws_lockfile_path.into_std_path_buf());
+
+    let lockfile = if lockfile_path.is_file() {
+        let messages = PlumbingCommand::read_lockfile(&lockfile_path)
+            .output()?
+            .into_messages()?;
+        Some(messages)
+    } else {
+        None
     };
 
     let locked_deps = {
-        let mut cmd = cargo_plumbing_bin();
-        cmd.args(["plumbing", "lock-dependencies"])
-            .arg("--manifest-path")
-            .arg(&manifest_path)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped());
-
-        let mut child = cmd.spawn().expect("failed to run read-manifest");
-        let mut stdout = child.stdout.take().expect("failed to take stdout");
-        let mut stdin = child.stdin.take().expect("failed to take stdin");
-
-        if let Some(ref lockfile) = lockfile {
-            let previous_lock: String = ReadLockfileOut::parse_stream(lockfile.as_slice())
-                .filter_map(Result::ok)
-                .filter(|msg| {
-                    matches!(
-                        msg,
-                        ReadLockfileOut::LockedPackage { .. }
-                            | ReadLockfileOut::UnusedPatches { .. }
-                    )
-                })
-                .map(|msg| serde_json::to_string(&msg))
-                .collect::<Result<Vec<_>, _>>()
-                .unwrap()
-                .join("\n");
-            stdin
-                .write_all(previous_lock.as_bytes())
-                .expect("failed to write to stdin");
-            drop(stdin);
-        }
-
-        let mut buffer = Vec::new();
-        stdout
-            .read_to_end(&mut buffer)
-            .expect("failed to read stdout");
-
-        child.wait().expect("failed to wait for lock-dependencies");
-        buffer
+        let previous_lock = lockfile.as_deref().map(forward).unwrap_or_default();
+        PlumbingCommand::lock_dependencies()
+            .manifest_path(&manifest_path)
+            .output_with_input(previous_lock)?
+            .into_messages()?
     };
 
-    if lockfile.is_some_and(|lockfile| lockfile != locked_deps) {
-        let mut cmd = cargo_plumbing_bin();
-        cmd.args(["plumbing", "write-lockfile"])
-            .arg("--lockfile-path")
-            .arg(&lockfile_path)
-            .stdin(Stdio::piped());
-
-        let mut child = cmd.spawn().expect("failed spawn lock-dependencies");
-        let mut stdin = child.stdin.take().expect("failed to take stdin");
-
-        stdin
-            .write_all(&locked_deps)
-            .expect("failed to write to stdin");
-        drop(stdin);
-
-        child.wait().expect("failed to wait for lock-dependencies");
+    if let Some(lockfile) = &lockfile {
+        let previous_lock = forward::<WriteLockfileIn>(lockfile);
+        let lock = forward::<WriteLockfileIn>(&locked_deps);
+        if serde_json::to_value(&previous_lock)? != serde_json::to_value(&lock)? {
+            PlumbingCommand::write_lockfile(&lockfile_path)
+                .output_with_input(lock)?
+                .into_messages()?;
+        }
     }
 
     let features = {
-        let ids = manifests
-            .iter()
-            .map(|msg| serde_json::to_string(&msg))
-            .collect::<Result<Vec<_>, _>>()?
-            .join("\n");
-
-        let mut cmd = cargo_plumbing_bin();
-        cmd.args(["plumbing", "resolve-features"])
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped());
-
+        let mut cmd = PlumbingCommand::resolve_features()
+            .manifest_path(&manifest_path)
+            .features(&args.features)
+            .bin(&args.bin)
+            .example(&args.example)
+            .test(&args.test)
+            .bench(&args.bench);
+        if args.all_features {
+            cmd = cmd.all_features();
+        }
+        if args.no_default_features {
+            cmd = cmd.no_default_features();
+        }
+        if args.bins {
+            cmd = cmd.bins();
+        }
         if args.examples {
-            cmd.arg("--examples");
+            cmd = cmd.examples();
         }
         if args.tests {
-            cmd.arg("--tests");
+            cmd = cmd.tests();
         }
         if args.benches {
-            cmd.arg("--benches");
+            cmd = cmd.benches();
         }
         if args.all_targets {
-            cmd.arg("--all-targets");
+            cmd = cmd.all_targets();
         }
-        cmd.args(["--example", &args.example.join(",")]);
-        cmd.args(["--test", &args.test.join(",")]);
-        cmd.args(["--bench", &args.bench.join(",")]);
 
-        let mut child = cmd.spawn().expect("failed to spawn resolve-features");
-
-        {
-            let mut stdin = child.stdin.take().expect("failed to take stdin");
-            stdin
-                .write_all(ids.as_bytes())
-                .expect("failed to write to stdin");
-            stdin
-                .write_all(&locked_deps)
-                .expect("failed to write to stdin");
-        }
-
-        let messages = {
-            let stdout = child.stdout.take().expect("failed to get stdout");
-            LocateManifestOut::parse_stream(BufReader::new(stdout))
-        };
-
-        child.wait().expect("failed to wait for resolve-features");
-        messages.collect::<Result<Vec<_>, _>>()?
+        let mut input = forward(&manifests);
+        input.extend(forward(&locked_deps));
+        cmd.output_with_input(input)?.into_messages()?
     };
 
     let _units = {
-        let ids = manifests
-            .iter()
-            .map(|msg| serde_json::to_string(&msg))
-            .collect::<Result<Vec<_>, _>>()?
-            .join("\n");
-        let features = features
-            .iter()
-            .map(|msg| serde_json::to_string(&msg))
-            .collect::<Result<Vec<_>, _>>()?
-            .join("\n");
-
-        let mut cmd = cargo_plumbing_bin();
-        cmd.args(["plumbing", "resolve-features"])
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped());
-
-        if let Some(manifest_path) = &args.manifest_path {
-            cmd.arg("--manifest-path");
-            cmd.arg(manifest_path);
-        }
-
-        let mut child = cmd.spawn().expect("failed to spawn resolve-features");
-
-        {
-            let mut stdin = child.stdin.take().expect("failed to take stdin");
-            stdin
-                .write_all(ids.as_bytes())
-                .expect("failed to write to stdin");
-            stdin
-                .write_all(&locked_deps)
-                .expect("failed to write to stdin");
-            stdin
-                .write_all(features.as_bytes())
-                .expect("failed to write to stdin");
-        }
-
-        let messages = {
-            let stdout = child.stdout.take().expect("failed to get stdout");
-            LocateManifestOut::parse_stream(BufReader::new(stdout))
-        };
-
-        child.wait().expect("failed to wait for resolve-features");
-        messages.collect::<Result<Vec<_>, _>>()?
+        let mut input = forward(&manifests);
+        input.extend(forward(&locked_deps));
+        input.extend(forward(&features));
+        PlumbingCommand::plan_build()
+            .manifest_path(&manifest_path)
+            .output_with_input(input)?
+            .into_messages()?
     };
 
     anyhow::bail!("check for {manifest_path} is not implemented!");
@@ -294,18 +172,12 @@ fn main() {
     }
 }
 
-/// [`Command`] for launching cargo-plumbing binary
-///
-/// The `CARGO_PLUMBING_BIN` env variable should point to a `cargo-plumbing` binary. It is used
-/// mostly for testing examples.
-///
-/// See <https://github.com/crate-ci/cargo-plumbing/issues/108>
-fn cargo_plumbing_bin() -> Command {
-    if let Ok(bin) = std::env::var("CARGO_PLUMBING_BIN") {
-        Command::new(bin)
-    } else {
-        let mut cmd = Command::new("cargo");
-        cmd.arg("run");
-        cmd
-    }
+/// Passes the output messages of one command on as the input of another, dropping the messages
+/// the other command doesn't take.
+fn forward<I: DeserializeOwned>(messages: &[impl Serialize]) -> Vec<I> {
+    messages
+        .iter()
+        .filter_map(|msg| serde_json::to_value(msg).ok())
+        .filter_map(|msg| serde_json::from_value(msg).ok())
+        .collect()
 }
@@ -1,13 +1,12 @@
 use std::collections::BTreeMap;
-use std::io::BufReader;
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
 
 use cargo::core::PackageIdSpec;
 use cargo::util::interning::InternedString;
 use cargo::CargoResult;
+use cargo_plumbing_schemas::client::PlumbingCommand;
 use cargo_plumbing_schemas::locate_manifest::LocateManifestOut;
-use cargo_plumbing_schemas::read_manifest::ReadManifestOut;
+use cargo_plumbing_schemas::read_manifest::{ManifestTarget, ReadManifestOut};
 use cargo_util_schemas::manifest::{Hints, RustVersion, VecStringOrBool};
 use clap::Parser;
 use semver::Version;
@@ -45,56 +44,42 @@ fn main() -> CargoResult<()> {
 
     // We first locate the manifest based on the provided path from `--manifest-path`.
     let manifest_path = {
-        let mut cmd = cargo_plumbing_bin();
-        cmd.args(["plumbing", "locate-manifest"])
-            .stdout(Stdio::piped());
+        let mut cmd = PlumbingCommand::locate_manifest();
 
         // The `--manifest-path` flag is used to help cargo-plumbing locate the manifest path by
         // giving it a starting point. When not using `--manifest-path`, cargo-plumbing uses the
         // current directory in which the command is invoked.
         if let Some(manifest_path) = &args.manifest_path {
-            cmd.arg("--manifest-path");
-            cmd.arg(manifest_path);
+            cmd = cmd.manifest_path(manifest_path);
         }
 
-        let mut child = cmd.spawn().expect("failed to run locate-manifest");
-        let stdout = child.stdout.take().expect("failed to get stdout");
-        let messages = LocateManifestOut::parse_stream(BufReader::new(stdout));
-
-        let mut manifest_path: Option<camino::Utf8PathBuf> = None;
+        let messages = cmd.output()?.into_messages()?;
 
         // The output of `locate-manifest` is in the form of a `ManifestLocation` message. We
-        // extract this information from stdout.
-        #[allow(clippy::never_loop)]
+        // extract this information from the output.
+        let mut manifest_path: Option<camino::Utf8PathBuf> = None;
         for message in messages {
-            match message.expect("failed to parse message") {
+            match message {
                 LocateManifestOut::ManifestLocation { manifest_path: m } => {
                     manifest_path = Some(m);
-                    break;
                 }
+                LocateManifestOut::SchemaVersion { .. }
+                | LocateManifestOut::ManifestCandidate { .. }
+                | LocateManifestOut::WorkspaceRoot { .. } => {}
             }
         }
 
-        child.wait().expect("failed to wait for locate-manifest");
         manifest_path.expect("failed to get manifest_path")
     };
 
     // Next, we read the workspace members. Since this example is a `cargo metadata --no-deps`
-    // example, the packages are from the workspace.
-    let ws_members: Vec<ReadManifestOut> = {
-        let mut cmd = cargo_plumbing_bin();
-        cmd.args(["plumbing", "read-manifest"])
-            .args(["--manifest-path", manifest_path.as_str()])
-            .arg("--workspace")
-            .stdout(Stdio::piped());
-
-        let mut child = cmd.spawn().expect("failed to run read-manifest");
-        let stdout = child.stdout.take().expect("failed to get stdout");
-        let messages = ReadManifestOut::parse_stream(BufReader::new(stdout));
-
-        child.wait().expect("failed to wait for read-manifest");
-        messages.collect::<Result<Vec<_>, _>>()?
-    };
+    // example, the packages are from the workspace. The targets are requested so the ones
+    // discovered from the package files are listed too.
+    let ws_members: Vec<ReadManifestOut> = PlumbingCommand::read_manifest(&manifest_path)
+        .workspace()
+        .targets()
+        .output()?
+        .into_messages()?;
 
     let mut workspace_root = None;
     let mut workspace_members = Vec::new();
@@ -113,6 +98,7 @@ fn main() -> CargoResult<()> {
                 path,
                 pkg_id,
                 manifest,
+                targets,
                 ..
             } => {
                 if let Some(ws) = &manifest.workspace {
@@ -183,7 +169,7 @@ fn main() -> CargoResult<()> {
                             description: package.normalized_description()?,
                             source: (),
                             dependencies: Vec::new(),
-                            targets: Vec::new(),
+                            targets: targets.iter().flatten().collect(),
                             features: BTreeMap::new(),
                             manifest_path: path.clone().into_std_path_buf(),
                             metadata: package.metadata.as_ref(),
@@ -217,6 +203,9 @@ fn main() -> CargoResult<()> {
                     }
                 }
             }
+            ReadManifestOut::SchemaVersion { .. }
+            | ReadManifestOut::Warning { .. }
+            | ReadManifestOut::Workspace { .. } => {}
         }
     }
 
@@ -253,7 +242,7 @@ struct SerializedPackage<'a> {
     description: Option<&'a String>,
     source: (),
     dependencies: Vec<()>,
-    targets: Vec<String>,
+    targets: Vec<&'a ManifestTarget>,
     features: BTreeMap<String, Vec<InternedString>>,
     manifest_path: PathBuf,
     metadata: Option<&'a toml::Value>,
@@ -289,22 +278,6 @@ struct CargoMetadataOutput<'a> {
     metadata: Option<&'a toml::Value>,
 }
 
-/// [`Command`] for launching cargo-plumbing binary
-///
-/// The `CARGO_PLUMBING_BIN` env variable should point to a `cargo-plumbing` binary. It is used
-/// mostly for testing examples.
-///
-/// See <https://github.com/crate-ci/cargo-plumbing/issues/108>
-fn cargo_plumbing_bin() -> Command {
-    if let Ok(bin) = std::env::var("CARGO_PLUMBING_BIN") {
-        Command::new(bin)
-    } else {
-        let mut cmd = Command::new("cargo");
-        cmd.arg("run");
-        cmd
-    }
-}
-
 /// The members and default-members values from the manifest may still be in their glob form. This
 /// utility function exist to resolve their glob values.
 fn members_paths(
@@ -1,4 +1,6 @@
 use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing::ops::check_message_format_version;
+use cargo_plumbing_schemas::SchemaVersion;
 use clap::{ArgAction, Parser};
 
 #[derive(Debug, Parser)]
@@ -23,6 +25,14 @@ pub(crate) struct PlumbingCommand {
     #[arg(global = true, long, value_name = "KEY=VALUE", action = ArgAction::Append)]
     pub(crate) config: Vec<String>,
 
+    /// Require message format VERSION and print it first
+    #[arg(global = true, long, value_name = "VERSION")]
+    pub(crate) message_format_version: Option<u32>,
+
+    /// Unstable (nightly-only) flags to Cargo
+    #[arg(global = true, short = 'Z', value_name = "FLAG", action = ArgAction::Append)]
+    pub(crate) unstable_features: Vec<String>,
+
     #[command(subcommand)]
     pub(crate) subcommand: crate::plumbing::Plumbing,
 }
@@ -35,7 +45,8 @@ impl Command {
                 let quiet = false;
                 let color = None;
                 let target_dir = None;
-                let unstable_flags = &[];
+                let mut unstable_flags = cmd.unstable_features.clone();
+                unstable_flags.extend(cmd.subcommand.unstable_flags(gctx)?);
 
                 gctx.configure(
                     verbose,
@@ -45,10 +56,21 @@ impl Command {
                     cmd.locked,
                     cmd.offline,
                     &target_dir,
-                    unstable_flags,
+                    &unstable_flags,
                     &cmd.config,
                 )?;
 
+                if let Some(version) = cmd.message_format_version {
+                    // The schema is printed as one JSON document rather than as messages, so
+                    // there is no stream to print the version at the start of.
+                    #[cfg(feature = "unstable-schema")]
+                    if cmd.subcommand.is_schema() {
+                        anyhow::bail!("`--message-format-version` isn't supported by `schema`");
+                    }
+                    check_message_format_version(version)?;
+                    gctx.shell().print_json(&SchemaVersion { version })?;
+                }
+
                 cmd.subcommand.exec(gctx)
             }
         }
@@ -2,26 +2,28 @@ use std::env;
 use std::fmt::Debug;
 use std::path::PathBuf;
 
-use camino::Utf8PathBuf;
-use cargo::util::important_paths::find_root_manifest_for_wd;
 use cargo::{CargoResult, GlobalContext};
-use cargo_plumbing_schemas::locate_manifest::LocateManifestOut;
+use cargo_plumbing::ops::{self, LocateManifestOptions};
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct Args {
     /// Path to the manifest file
     #[arg(long)]
     manifest_path: Option<PathBuf>,
+    /// Also locate the root manifest of the workspace
+    #[arg(long)]
+    workspace: bool,
 }
 
 pub(crate) fn exec(gctx: &mut GlobalContext, args: Args) -> CargoResult<()> {
     let path = args.manifest_path.unwrap_or(env::current_dir()?);
-    let manifest_path = find_root_manifest_for_wd(&path)?;
-
-    let location = LocateManifestOut::ManifestLocation {
-        manifest_path: Utf8PathBuf::try_from(manifest_path)?,
+    let opts = LocateManifestOptions {
+        workspace: args.workspace,
     };
-    gctx.shell().print_json(&location)?;
+
+    for msg in ops::locate_manifest(gctx, &gctx.cwd().join(path), &opts)? {
+        gctx.shell().print_json(&msg)?;
+    }
 
     Ok(())
 }
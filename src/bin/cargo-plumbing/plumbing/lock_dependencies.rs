@@ -3,19 +3,11 @@ use std::io::{BufReader, IsTerminal};
 use std::path::PathBuf;
 use std::{env, io};
 
-use cargo::core::registry::PackageRegistry;
-use cargo::core::resolver::{CliFeatures, HasDevUnits};
-use cargo::core::{ResolveVersion, Workspace};
-use cargo::ops::resolve_with_previous;
-use cargo::sources::SourceConfigMap;
+use cargo::core::Workspace;
 use cargo::{CargoResult, GlobalContext};
-use cargo_plumbing::cargo::core::resolver::encode::{
-    encodable_resolve_node, encodable_source_id, EncodeState,
-};
-use cargo_plumbing::ops::resolve::{into_resolve, normalize_dependency, normalize_packages};
-use cargo_plumbing_schemas::lock_dependencies::{LockDependenciesIn, LockDependenciesOut};
-use cargo_plumbing_schemas::lockfile::NormalizedPatch;
-use cargo_util_schemas::lockfile::TomlLockfileDependency;
+use cargo_plumbing::ops::{self, LockDependenciesOptions};
+use cargo_plumbing_schemas::lock_dependencies::LockDependenciesIn;
+use cargo_util_schemas::manifest::RustVersion;
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct Args {
@@ -26,6 +18,74 @@ pub(crate) struct Args {
     // See: https://github.com/crate-ci/cargo-plumbing/issues/82
     #[arg(long)]
     manifest_path: Option<PathBuf>,
+    /// List of features to activate
+    #[arg(long, short = 'F')]
+    features: Vec<String>,
+    /// Activate all available features
+    #[arg(long)]
+    all_features: bool,
+    /// Do not activate the `default` feature
+    #[arg(long)]
+    no_default_features: bool,
+    /// Do not resolve dev-dependencies
+    #[arg(long)]
+    no_dev_deps: bool,
+    /// `allow` or `fallback` to deps supporting rust-version
+    #[arg(long, value_name = "MODE", hide_possible_values = true)]
+    incompatible_rust_versions: Option<IncompatibleRustVersions>,
+    /// Fall back to deps supporting this rust-version
+    #[arg(
+        long,
+        value_name = "VER",
+        conflicts_with = "incompatible_rust_versions"
+    )]
+    rust_version: Option<RustVersion>,
+    /// Resolve to the lowest versions allowed (nightly-only)
+    #[arg(long)]
+    minimal_versions: bool,
+    /// Resolve direct deps to the lowest versions allowed (nightly-only)
+    #[arg(long, conflicts_with = "minimal_versions")]
+    direct_minimal_versions: bool,
+    /// Report locked packages yanked from their registry
+    #[arg(long)]
+    report_yanked: bool,
+    /// Report sources replaced by `[source]` config
+    #[arg(long)]
+    report_source_replacements: bool,
+    /// Report packages overridden by `[patch]` or `[replace]`
+    #[arg(long)]
+    report_patches: bool,
+}
+
+impl Args {
+    /// Cargo's resolver takes the version ordering from the unstable flags of the
+    /// [`GlobalContext`], so these have to be passed when configuring it.
+    ///
+    /// Those are only accepted on the nightly channel, so the options requiring them are rejected
+    /// on others.
+    pub(crate) fn unstable_flags(&self, gctx: &GlobalContext) -> CargoResult<Vec<String>> {
+        let flag = if self.minimal_versions {
+            "minimal-versions"
+        } else if self.direct_minimal_versions {
+            "direct-minimal-versions"
+        } else {
+            return Ok(Vec::new());
+        };
+        if !gctx.nightly_features_allowed {
+            anyhow::bail!("`--{flag}` is only available on the nightly channel of Cargo");
+        }
+        Ok(vec![flag.to_owned()])
+    }
+}
+
+/// Whether to resolve against the workspace's `rust-version`, like Cargo's
+/// `resolver.incompatible-rust-versions` config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum IncompatibleRustVersions {
+    /// Pick the newest versions regardless of their `rust-version`
+    Allow,
+    /// Prefer versions compatible with the workspace's `rust-version`
+    Fallback,
 }
 
 pub(crate) fn exec(gctx: &GlobalContext, args: Args) -> CargoResult<()> {
@@ -37,89 +97,45 @@ pub(crate) fn exec(gctx: &GlobalContext, args: Args) -> CargoResult<()> {
         .manifest_path
         .unwrap_or(env::current_dir()?.join("Cargo.toml"));
     let path = gctx.cwd().join(manifest_path);
-    let ws = Workspace::new(&path, gctx)?;
+    let mut ws = Workspace::new(&path, gctx)?;
+    if args.rust_version.is_some() {
+        // The given `rust-version` is honored in place of the workspace's.
+        ws.set_resolve_honors_rust_version(Some(false));
+    } else if let Some(mode) = args.incompatible_rust_versions {
+        ws.set_resolve_honors_rust_version(Some(mode == IncompatibleRustVersions::Fallback));
+    }
 
     let stdin = io::stdin();
     if stdin.is_terminal() {
         anyhow::bail!("input must be piped from a file or another command");
     }
 
-    let messages = LockDependenciesIn::parse_stream(BufReader::new(stdin));
-
-    let mut locked_packages = Vec::new();
-    let mut unused_patches = None;
-
-    for message in messages {
-        match message? {
-            LockDependenciesIn::LockedPackage { package } => locked_packages.push(package),
-            LockDependenciesIn::UnusedPatches { unused } => unused_patches = Some(unused),
-        }
-    }
+    let messages =
+        LockDependenciesIn::parse_stream(BufReader::new(stdin)).collect::<Result<Vec<_>, _>>()?;
 
-    let previous_resolve = if !locked_packages.is_empty() {
-        Some(into_resolve(
-            &ws,
-            locked_packages,
-            unused_patches.unwrap_or_default(),
-        )?)
-    } else {
-        None
+    let opts = LockDependenciesOptions {
+        features: args.features,
+        all_features: args.all_features,
+        no_default_features: args.no_default_features,
+        no_dev_deps: args.no_dev_deps,
+        report_rust_versions: args.incompatible_rust_versions.is_some()
+            || args.rust_version.is_some(),
+        rust_version: args.rust_version,
+        report_yanked: args.report_yanked,
+        report_source_replacements: args.report_source_replacements,
+        report_patches: args.report_patches,
     };
-    let source_config = SourceConfigMap::new(gctx)?;
-    let mut registry = PackageRegistry::new_with_source_config(gctx, source_config)?;
 
-    let resolve = resolve_with_previous(
-        &mut registry,
-        &ws,
-        &CliFeatures::new_all(true),
-        HasDevUnits::Yes,
-        previous_resolve.as_ref(),
-        None,
-        &[],
-        true,
-    )?;
-
-    let mut ids: Vec<_> = resolve.iter().collect();
-    ids.sort();
-    let state = EncodeState::new(&resolve);
-    let packages = ids
-        .iter()
-        .map(|&id| encodable_resolve_node(id, &resolve, &state))
-        .collect::<Vec<_>>();
-    let metadata = resolve.metadata().clone();
-
-    let version = match resolve.version() {
-        ResolveVersion::V5 => 5,
-        ResolveVersion::V4 => 4,
-        ResolveVersion::V3 => 3,
-        ResolveVersion::V2 => 2,
-        ResolveVersion::V1 => 1,
+    let out = match ops::lock_dependencies(&ws, &opts, messages) {
+        Ok(out) => out,
+        Err(err) => {
+            if let Some(msg) = ops::lock_dependencies::resolve_error(&ws, &err) {
+                gctx.shell().print_json(&msg)?;
+            }
+            return Err(err);
+        }
     };
-    gctx.shell()
-        .print_json(&LockDependenciesOut::Lockfile { version })?;
-
-    for package in normalize_packages(None, Some(packages), Some(metadata), None)? {
-        gctx.shell()
-            .print_json(&LockDependenciesOut::LockedPackage { package })?;
-    }
-
-    let unused: Vec<_> = resolve
-        .unused_patches()
-        .iter()
-        .map(|id| {
-            normalize_dependency(TomlLockfileDependency {
-                name: id.name().to_string(),
-                version: id.version().to_string(),
-                source: encodable_source_id(id.source_id(), resolve.version()),
-                dependencies: None,
-                replace: None,
-                checksum: resolve.checksums().get(id).and_then(|x| x.clone()),
-            })
-        })
-        .collect::<Result<Vec<_>, _>>()?;
-    if !unused.is_empty() {
-        let unused = NormalizedPatch { unused };
-        let msg = LockDependenciesOut::UnusedPatches { unused };
+    for msg in out {
         gctx.shell().print_json(&msg)?;
     }
 
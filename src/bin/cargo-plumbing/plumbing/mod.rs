@@ -1,17 +1,27 @@
+use std::env;
+use std::path::PathBuf;
+
+use cargo::core::Workspace;
 use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing::ops;
 
 pub(crate) mod locate_manifest;
 pub(crate) mod lock_dependencies;
 pub(crate) mod plan_build;
+pub(crate) mod query_index;
 pub(crate) mod read_lockfile;
 pub(crate) mod read_manifest;
 pub(crate) mod resolve_features;
+#[cfg(feature = "unstable-schema")]
+pub(crate) mod schema;
+pub(crate) mod validate;
+pub(crate) mod vendor;
 pub(crate) mod write_lockfile;
 
 #[derive(Debug, clap::Subcommand)]
 #[command(styles = clap_cargo::style::CLAP_STYLING)]
 pub(crate) enum Plumbing {
-    /// Locate the root manifest file
+    /// Locate the manifest file of a package or workspace
     #[command()]
     LocateManifest(locate_manifest::Args),
     /// Read the lockfile
@@ -32,9 +42,36 @@ pub(crate) enum Plumbing {
     /// Plan a build by creating the unit graph
     #[command()]
     PlanBuild(plan_build::Args),
+    /// Query the registry index for the versions of packages
+    #[command()]
+    QueryIndex(query_index::Args),
+    /// Print the JSON schema of a command's messages
+    #[cfg(feature = "unstable-schema")]
+    #[command()]
+    Schema(schema::Args),
+    /// Validate the input messages of a command
+    #[command()]
+    Validate(validate::Args),
+    /// Vendor the sources of locked packages
+    #[command()]
+    Vendor(vendor::Args),
 }
 
 impl Plumbing {
+    /// The unstable flags to configure the [`GlobalContext`] with
+    pub(crate) fn unstable_flags(&self, gctx: &GlobalContext) -> CargoResult<Vec<String>> {
+        match self {
+            Self::LockDependencies(args) => args.unstable_flags(gctx),
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    /// Whether this is the `schema` command, which doesn't print messages
+    #[cfg(feature = "unstable-schema")]
+    pub(crate) fn is_schema(&self) -> bool {
+        matches!(self, Self::Schema(_))
+    }
+
     pub(crate) fn exec(self, gctx: &mut GlobalContext) -> CargoResult<()> {
         match self {
             Self::LocateManifest(args) => locate_manifest::exec(gctx, args),
@@ -44,6 +81,26 @@ impl Plumbing {
             Self::WriteLockfile(args) => write_lockfile::exec(gctx, args),
             Self::ResolveFeatures(args) => resolve_features::exec(gctx, args),
             Self::PlanBuild(args) => plan_build::exec(gctx, args),
+            Self::QueryIndex(args) => query_index::exec(gctx, args),
+            #[cfg(feature = "unstable-schema")]
+            Self::Schema(args) => schema::exec(gctx, args),
+            Self::Validate(args) => validate::exec(gctx, args),
+            Self::Vendor(args) => vendor::exec(gctx, args),
         }
     }
 }
+
+/// The lockfile to use, defaulting to the one of the workspace at `manifest_path`
+pub(crate) fn lockfile_path(
+    gctx: &GlobalContext,
+    lockfile_path: Option<PathBuf>,
+    manifest_path: Option<PathBuf>,
+) -> CargoResult<PathBuf> {
+    if let Some(lockfile_path) = lockfile_path {
+        return Ok(lockfile_path);
+    }
+
+    let manifest_path = manifest_path.unwrap_or(env::current_dir()?.join("Cargo.toml"));
+    let ws = Workspace::new(&gctx.cwd().join(manifest_path), gctx)?;
+    ops::lockfile_path(&ws)
+}
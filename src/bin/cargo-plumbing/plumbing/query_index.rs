@@ -0,0 +1,26 @@
+use std::fmt::Debug;
+
+use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing::ops::{self, QueryIndexOptions};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+    /// Packages to query, as NAME or NAME@VERSION_REQ
+    #[arg(required = true, value_name = "PACKAGE")]
+    packages: Vec<String>,
+    /// Registry to query instead of crates.io
+    #[arg(long)]
+    registry: Option<String>,
+}
+
+pub(crate) fn exec(gctx: &GlobalContext, args: Args) -> CargoResult<()> {
+    let opts = QueryIndexOptions {
+        registry: args.registry,
+    };
+
+    for msg in ops::query_index(gctx, &opts, &args.packages)? {
+        gctx.shell().print_json(&msg)?;
+    }
+
+    Ok(())
+}
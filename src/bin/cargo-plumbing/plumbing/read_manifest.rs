@@ -1,14 +1,10 @@
+use std::io::{self, BufReader, IsTerminal};
 use std::path::PathBuf;
 
-use anyhow::Context as _;
-use camino::Utf8PathBuf;
-use cargo::core::{
-    find_workspace_root, EitherManifest, MaybePackage, SourceId, Workspace, WorkspaceConfig,
-};
-use cargo::util::toml::read_manifest;
+use cargo::core::Workspace;
 use cargo::{CargoResult, GlobalContext};
-use cargo_plumbing_schemas::read_manifest::ReadManifestOut;
-use cargo_util::paths;
+use cargo_plumbing::ops::{self, ReadManifestOptions};
+use cargo_plumbing_schemas::read_manifest::ReadManifestIn;
 
 #[derive(Debug, clap::Args)]
 pub(crate) struct Args {
@@ -18,115 +14,51 @@ pub(crate) struct Args {
     /// Read all manifests in the workspace
     #[clap(long, default_value_t = false)]
     workspace: bool,
+    /// Include the manifests as written
+    #[clap(long, default_value_t = false)]
+    original: bool,
+    /// Include where each table and key is written
+    #[clap(long, default_value_t = false)]
+    spans: bool,
+    /// Include the targets of each package
+    #[clap(long, default_value_t = false)]
+    targets: bool,
+    /// Report warnings such as unused keys
+    #[clap(long, default_value_t = false)]
+    report_warnings: bool,
+    /// Report the members of the workspace
+    #[clap(long, default_value_t = false, requires = "workspace")]
+    report_workspace: bool,
+    /// Read the manifests of the locked packages from stdin
+    #[clap(long, default_value_t = false, conflicts_with = "workspace")]
+    dependencies: bool,
 }
 
 pub(crate) fn exec(gctx: &mut GlobalContext, args: Args) -> CargoResult<()> {
-    let requested_manifest_path = gctx.cwd().join(args.manifest_path);
-
-    if args.workspace {
-        let workspace = Workspace::new(&requested_manifest_path, gctx)?;
-
-        // Here, we print the root Package or the Virtual Manifest of the workspace.
-        let msg = match workspace.root_maybe() {
-            MaybePackage::Package(pkg) => ReadManifestOut::Manifest {
-                workspace: true,
-                path: Utf8PathBuf::try_from(gctx.cwd().join(pkg.manifest_path()))?,
-                pkg_id: Some(pkg.package_id().to_spec()),
-                manifest: pkg.manifest().normalized_toml().clone(),
-            },
-            MaybePackage::Virtual(v) => ReadManifestOut::Manifest {
-                workspace: true,
-                path: Utf8PathBuf::try_from(gctx.cwd().join(workspace.root_manifest()))?,
-                pkg_id: None,
-                manifest: v.normalized_toml().clone(),
-            },
-        };
-        gctx.shell().print_json(&msg)?;
+    let opts = ReadManifestOptions {
+        workspace: args.workspace,
+        original: args.original,
+        spans: args.spans,
+        targets: args.targets,
+        report_warnings: args.report_warnings,
+        report_workspace: args.report_workspace,
+    };
 
-        // We don't want to print the same manifest twice. This is why we're filtering the
-        // workspace members to make sure only those that are not root manifest are printed in this
-        // stage.
-        for member in workspace
-            .members()
-            .filter(|p| p.manifest_path() != workspace.root_manifest())
-        {
-            let msg = ReadManifestOut::Manifest {
-                workspace: false,
-                path: Utf8PathBuf::try_from(gctx.cwd().join(member.manifest_path()))?,
-                pkg_id: Some(member.package_id().to_spec()),
-                manifest: member.manifest().normalized_toml().clone(),
-            };
-            gctx.shell().print_json(&msg)?;
+    let out = if args.dependencies {
+        let stdin = io::stdin();
+        if stdin.is_terminal() {
+            anyhow::bail!("input must be piped from a file or another command");
         }
-    } else {
-        // As this is the branch without `--workspace`, we want to only read one manifest.
-        let source_id = SourceId::for_manifest_path(&requested_manifest_path)?;
-        let (pkg_id, ws_config, manifest) =
-            match read_manifest(&requested_manifest_path, source_id, gctx)? {
-                EitherManifest::Real(r) => (
-                    Some(r.package_id().to_spec()),
-                    r.workspace_config().clone(),
-                    r.normalized_toml().clone(),
-                ),
-                EitherManifest::Virtual(v) => (
-                    None,
-                    v.workspace_config().clone(),
-                    v.normalized_toml().clone(),
-                ),
-            };
-
-        // If the current manifest is a workspace member, find its workspace manifest path.
-        let ws_manifest_path = match ws_config {
-            WorkspaceConfig::Root(..) => {
-                // The current manifest is already the workspace root, so there is no other
-                // workspace root to find.
-                None
-            }
-            WorkspaceConfig::Member {
-                root: Some(path_to_root),
-            } => {
-                // The member's `[package.workspace]` key points to the root directory
-                Some(
-                    requested_manifest_path
-                        .parent()
-                        .context("manifest-path can't be root")?
-                        .join(path_to_root)
-                        .join("Cargo.toml"),
-                )
-            }
-            WorkspaceConfig::Member { root: None } => {
-                // Find the root directory by searching upwards the filesystem.
-                find_workspace_root(&requested_manifest_path, gctx)?
-            }
-        };
 
-        let msg = ReadManifestOut::Manifest {
-            workspace: ws_manifest_path.is_none(),
-            path: Utf8PathBuf::try_from(requested_manifest_path.clone())?,
-            pkg_id,
-            manifest,
-        };
+        let ws = Workspace::new(&gctx.cwd().join(&args.manifest_path), gctx)?;
+        let messages =
+            ReadManifestIn::parse_stream(BufReader::new(stdin)).collect::<Result<Vec<_>, _>>()?;
+        ops::read_dependency_manifests(&ws, &opts, messages)?
+    } else {
+        ops::read_manifest(gctx, &args.manifest_path, &opts)?
+    };
+    for msg in out {
         gctx.shell().print_json(&msg)?;
-
-        if let Some(ws_manifest_path) = ws_manifest_path {
-            let ws_manifest_path = paths::normalize_path(&gctx.cwd().join(ws_manifest_path));
-            let source_id = SourceId::for_manifest_path(&ws_manifest_path)?;
-
-            let (pkg_id, manifest) = match read_manifest(&ws_manifest_path, source_id, gctx)? {
-                EitherManifest::Real(r) => {
-                    (Some(r.package_id().to_spec()), r.normalized_toml().clone())
-                }
-                EitherManifest::Virtual(v) => (None, v.normalized_toml().clone()),
-            };
-
-            let msg = ReadManifestOut::Manifest {
-                workspace: true,
-                path: Utf8PathBuf::try_from(ws_manifest_path.clone())?,
-                pkg_id,
-                manifest,
-            };
-            gctx.shell().print_json(&msg)?;
-        }
     }
 
     Ok(())
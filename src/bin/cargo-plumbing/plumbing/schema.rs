@@ -0,0 +1,58 @@
+use std::fmt::Debug;
+use std::io::Write as _;
+
+use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing::ops::Command;
+use cargo_plumbing_schemas::locate_manifest::LocateManifestOut;
+use cargo_plumbing_schemas::lock_dependencies::{LockDependenciesIn, LockDependenciesOut};
+use cargo_plumbing_schemas::plan_build::{PlanBuildIn, PlanBuildOut};
+use cargo_plumbing_schemas::query_index::QueryIndexOut;
+use cargo_plumbing_schemas::read_lockfile::ReadLockfileOut;
+use cargo_plumbing_schemas::read_manifest::{ReadManifestIn, ReadManifestOut};
+use cargo_plumbing_schemas::resolve_features::{ResolveFeaturesIn, ResolveFeaturesOut};
+use cargo_plumbing_schemas::validate::ValidateOut;
+use cargo_plumbing_schemas::vendor::{VendorIn, VendorOut};
+use cargo_plumbing_schemas::write_lockfile::WriteLockfileIn;
+use schemars::schema_for;
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+    /// The command to print the schema for
+    #[arg(value_enum, hide_possible_values = true)]
+    command: Command,
+
+    /// Print the schema of the input messages
+    #[arg(long = "in", conflicts_with = "output")]
+    input: bool,
+
+    /// Print the schema of the output messages (default)
+    #[arg(long = "out")]
+    output: bool,
+}
+
+pub(crate) fn exec(gctx: &GlobalContext, args: Args) -> CargoResult<()> {
+    let schema = match (args.command, args.input) {
+        (Command::LocateManifest, false) => schema_for!(LocateManifestOut),
+        (Command::ReadLockfile, false) => schema_for!(ReadLockfileOut),
+        (Command::ReadManifest, false) => schema_for!(ReadManifestOut),
+        (Command::ReadManifest, true) => schema_for!(ReadManifestIn),
+        (Command::LockDependencies, false) => schema_for!(LockDependenciesOut),
+        (Command::LockDependencies, true) => schema_for!(LockDependenciesIn),
+        (Command::WriteLockfile, true) => schema_for!(WriteLockfileIn),
+        (Command::ResolveFeatures, false) => schema_for!(ResolveFeaturesOut),
+        (Command::ResolveFeatures, true) => schema_for!(ResolveFeaturesIn),
+        (Command::PlanBuild, false) => schema_for!(PlanBuildOut),
+        (Command::PlanBuild, true) => schema_for!(PlanBuildIn),
+        (Command::QueryIndex, false) => schema_for!(QueryIndexOut),
+        (Command::Validate, false) => schema_for!(ValidateOut),
+        (Command::Vendor, false) => schema_for!(VendorOut),
+        (Command::Vendor, true) => schema_for!(VendorIn),
+        (command, true) => anyhow::bail!("`{command}` doesn't read input messages"),
+        (command, false) => anyhow::bail!("`{command}` doesn't print output messages"),
+    };
+
+    let dump = serde_json::to_string_pretty(&schema)?;
+    writeln!(gctx.shell().out(), "{dump}")?;
+
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use std::fmt::Debug;
+use std::io::{self, IsTerminal};
+
+use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing::ops::{self, Command};
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+    /// The command whose input to validate
+    #[arg(value_enum, hide_possible_values = true)]
+    command: Command,
+}
+
+pub(crate) fn exec(gctx: &GlobalContext, args: Args) -> CargoResult<()> {
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        anyhow::bail!("input must be piped from a file or another command");
+    }
+
+    let diagnostics = ops::validate(args.command, stdin.lock())?;
+    for msg in &diagnostics {
+        gctx.shell().print_json(msg)?;
+    }
+
+    if !diagnostics.is_empty() {
+        anyhow::bail!("invalid `{}` input", args.command);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use std::io::{BufReader, IsTerminal};
+use std::path::PathBuf;
+use std::{env, io};
+
+use cargo::core::Workspace;
+use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing::ops;
+use cargo_plumbing_schemas::vendor::VendorIn;
+
+#[derive(Debug, clap::Args)]
+pub(crate) struct Args {
+    /// Directory to vendor the packages into
+    #[arg(default_value = "vendor")]
+    path: PathBuf,
+    /// Path to the manifest file
+    // HACK: We are reading manifests from disk and not purely from stdin because of cargo API
+    // limitations.
+    //
+    // See: https://github.com/crate-ci/cargo-plumbing/issues/82
+    #[arg(long)]
+    manifest_path: Option<PathBuf>,
+}
+
+pub(crate) fn exec(gctx: &GlobalContext, args: Args) -> CargoResult<()> {
+    let stdin = io::stdin();
+    if stdin.is_terminal() {
+        anyhow::bail!("input must be piped from a file or another command");
+    }
+
+    let manifest_path = args
+        .manifest_path
+        .unwrap_or(env::current_dir()?.join("Cargo.toml"));
+    let path = gctx.cwd().join(manifest_path);
+    let ws = Workspace::new(&path, gctx)?;
+
+    let messages = VendorIn::parse_stream(BufReader::new(stdin)).collect::<Result<Vec<_>, _>>()?;
+
+    for msg in ops::vendor(&ws, &args.path, messages)? {
+        gctx.shell().print_json(&msg)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,87 @@
+use std::path::{Path, PathBuf};
+
+use camino::Utf8PathBuf;
+use cargo::core::Workspace;
+use cargo::util::important_paths::find_root_manifest_for_wd;
+use cargo::util::toml::is_embedded;
+use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing_schemas::locate_manifest::LocateManifestOut;
+use cargo_util::paths;
+
+use crate::ops::lockfile_path;
+
+/// Options for [`locate_manifest`]
+#[derive(Debug, Default)]
+pub struct LocateManifestOptions {
+    /// Also locate the root manifest of the workspace, reporting the manifests walked to find it
+    pub workspace: bool,
+}
+
+/// Locates the nearest manifest for `path`, searching upwards the filesystem.
+///
+/// A single-file package passed as `path` is its own manifest, which requires `-Zscript`.
+///
+/// With [`LocateManifestOptions::workspace`], the root manifest of the enclosing workspace is
+/// located as well, like `cargo locate-project --workspace`.
+pub fn locate_manifest(
+    gctx: &GlobalContext,
+    path: &Path,
+    opts: &LocateManifestOptions,
+) -> CargoResult<Vec<LocateManifestOut>> {
+    let manifest_path = if is_embedded(path) {
+        if !gctx.cli_unstable().script {
+            anyhow::bail!("embedded manifest `{}` requires `-Zscript`", path.display());
+        }
+        path.to_owned()
+    } else {
+        find_root_manifest_for_wd(path)?
+    };
+
+    let mut out = vec![LocateManifestOut::ManifestLocation {
+        manifest_path: Utf8PathBuf::try_from(manifest_path.clone())?,
+    }];
+
+    if opts.workspace {
+        let ws = Workspace::new(&manifest_path, gctx)?;
+        let root = ws.root_manifest();
+        for candidate in candidate_manifests(gctx, &manifest_path) {
+            let is_root = candidate == root;
+            out.push(LocateManifestOut::ManifestCandidate {
+                manifest_path: Utf8PathBuf::try_from(candidate)?,
+            });
+            if is_root {
+                break;
+            }
+        }
+        let lockfile_path = lockfile_path(&ws)?;
+        out.push(LocateManifestOut::WorkspaceRoot {
+            manifest_path: Utf8PathBuf::try_from(root.to_owned())?,
+            lockfile_path: Utf8PathBuf::try_from(lockfile_path)?,
+        });
+    }
+
+    Ok(out)
+}
+
+/// The manifests Cargo considers as the workspace root of `manifest_path`, nearest first.
+///
+/// Like Cargo, the search stops at `target/package` and doesn't go above `CARGO_HOME`.
+fn candidate_manifests<'a>(
+    gctx: &'a GlobalContext,
+    manifest_path: &'a Path,
+) -> impl Iterator<Item = PathBuf> + 'a {
+    let home = gctx.home().as_path_unlocked();
+    let mut past_home = false;
+    let ancestors = paths::ancestors(manifest_path, None)
+        .skip(2)
+        .take_while(|dir| !dir.ends_with("target/package"))
+        .take_while(move |dir| {
+            let stop = past_home;
+            past_home = *dir == home;
+            !stop
+        })
+        .map(|dir| dir.join("Cargo.toml"))
+        .filter(|candidate| candidate.exists());
+
+    std::iter::once(manifest_path.to_owned()).chain(ancestors)
+}
@@ -0,0 +1,822 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use cargo::core::dependency::DepKind;
+use cargo::core::registry::PackageRegistry;
+use cargo::core::resolver::{CliFeatures, HasDevUnits, ResolveError};
+use cargo::core::{
+    Dependency, MaybePackage, PackageId, Resolve, ResolveVersion, SourceId, Summary, Workspace,
+};
+use cargo::ops::resolve_with_previous;
+use cargo::sources::{IndexSummary, SourceConfigMap, CRATES_IO_INDEX, CRATES_IO_REGISTRY};
+use cargo::util::interning::InternedString;
+use cargo::util::{CanonicalUrl, Graph, OptVersionReq};
+use cargo::CargoResult;
+use cargo_plumbing_schemas::lock_dependencies::{
+    LockDependenciesIn, LockDependenciesOut, OverriddenDependency, RejectedReason,
+    ResolveCandidate, ResolveRequirement,
+};
+use cargo_plumbing_schemas::lockfile::NormalizedPatch;
+use cargo_util_schemas::core::PartialVersion;
+use cargo_util_schemas::lockfile::TomlLockfileDependency;
+use cargo_util_schemas::manifest::RustVersion;
+use url::Url;
+
+use crate::cargo::core::resolver::encode::{
+    encodable_resolve_node, encodable_source_id, EncodeState,
+};
+use crate::ops::check_message_format_version;
+use crate::ops::resolve::{
+    into_resolve, normalize_dependency, normalize_packages, query, query_rejected,
+    source_replacements,
+};
+
+/// Options for [`lock_dependencies`]
+///
+/// By default, the dependencies of all features and dev-dependencies are locked, like
+/// `cargo generate-lockfile` does. Selecting features with `features` or `no_default_features`,
+/// or leaving out dev-dependencies, produces a partial lock instead.
+///
+/// The newest allowed versions are preferred unless the `minimal-versions` or
+/// `direct-minimal-versions` unstable flags are set on the
+/// [`GlobalContext`](cargo::GlobalContext).
+#[derive(Debug, Default)]
+pub struct LockDependenciesOptions {
+    /// List of features to activate
+    pub features: Vec<String>,
+    /// Activate all available features
+    pub all_features: bool,
+    /// Do not activate the `default` feature
+    pub no_default_features: bool,
+    /// Do not resolve dev-dependencies
+    pub no_dev_deps: bool,
+    /// Resolve against this `rust-version` instead of the workspace's
+    ///
+    /// Locked packages requiring a newer Rust are swapped for the newest version their dependents
+    /// allow that supports it, like the `fallback` mode of the resolver. The workspace shouldn't
+    /// honor its own `rust-version` then, see [`Workspace::set_resolve_honors_rust_version`].
+    pub rust_version: Option<RustVersion>,
+    /// Report locked packages affected by the honored `rust-version`
+    ///
+    /// Whether the resolver falls back to versions compatible with the workspace's
+    /// `rust-version` is up to [`Workspace::set_resolve_honors_rust_version`].
+    pub report_rust_versions: bool,
+    /// Report locked packages yanked from their registry
+    pub report_yanked: bool,
+    /// Report the sources of locked packages replaced by `[source]` tables
+    pub report_source_replacements: bool,
+    /// Report the locked packages overridden by `[patch]` or `[replace]` tables
+    pub report_patches: bool,
+}
+
+/// Resolves the dependencies of `ws`, using the locked packages in `input` as the previous
+/// resolve.
+pub fn lock_dependencies(
+    ws: &Workspace<'_>,
+    opts: &LockDependenciesOptions,
+    input: impl IntoIterator<Item = LockDependenciesIn>,
+) -> CargoResult<Vec<LockDependenciesOut>> {
+    let gctx = ws.gctx();
+
+    let mut locked_packages = Vec::new();
+    let mut unused_patches = None;
+
+    for message in input {
+        match message {
+            LockDependenciesIn::SchemaVersion { version } => check_message_format_version(version)?,
+            LockDependenciesIn::LockedPackage { package } => locked_packages.push(package),
+            LockDependenciesIn::UnusedPatches { unused } => unused_patches = Some(unused),
+        }
+    }
+
+    let previous_resolve = if !locked_packages.is_empty() {
+        Some(into_resolve(
+            ws,
+            locked_packages,
+            unused_patches.unwrap_or_default(),
+        )?)
+    } else {
+        None
+    };
+    let source_config = SourceConfigMap::new(gctx)?;
+    let mut registry = PackageRegistry::new_with_source_config(gctx, source_config)?;
+
+    let select_features =
+        !opts.all_features && (!opts.features.is_empty() || opts.no_default_features);
+    let (cli_features, specs) = if select_features {
+        let cli_features =
+            CliFeatures::from_command_line(&opts.features, false, !opts.no_default_features)?;
+        // Cargo only resolves without all features when the packages to resolve are given.
+        let specs = ws
+            .members()
+            .map(|pkg| pkg.package_id().to_spec())
+            .collect::<Vec<_>>();
+        (cli_features, specs)
+    } else {
+        (CliFeatures::new_all(true), Vec::new())
+    };
+    let has_dev_units = if opts.no_dev_deps {
+        HasDevUnits::No
+    } else {
+        HasDevUnits::Yes
+    };
+    let partial = select_features || opts.no_dev_deps;
+
+    let mut resolve = resolve_with_previous(
+        &mut registry,
+        ws,
+        &cli_features,
+        has_dev_units,
+        previous_resolve.as_ref(),
+        None,
+        &specs,
+        true,
+    )?;
+
+    if let Some(rust_version) = &opts.rust_version {
+        // The resolver only falls back to the `rust-version` of the workspace, so packages
+        // requiring a newer Rust are pinned to compatible versions and resolved again instead.
+        // Each package is swapped at most once, in case the resolver can't keep it pinned.
+        let mut swapped = HashSet::new();
+        loop {
+            let fallbacks = rust_version_fallbacks(&mut registry, &resolve, rust_version)?
+                .into_iter()
+                .filter(|(id, _)| swapped.insert(*id))
+                .collect::<HashMap<_, _>>();
+            if fallbacks.is_empty() {
+                break;
+            }
+
+            let pinned = pin_fallbacks(&resolve, &fallbacks);
+            // Patches can only be registered once with a registry.
+            let source_config = SourceConfigMap::new(gctx)?;
+            registry = PackageRegistry::new_with_source_config(gctx, source_config)?;
+            resolve = resolve_with_previous(
+                &mut registry,
+                ws,
+                &cli_features,
+                has_dev_units,
+                Some(&pinned),
+                None,
+                &specs,
+                true,
+            )?;
+        }
+    }
+
+    let mut ids: Vec<_> = resolve.iter().collect();
+    ids.sort();
+    let state = EncodeState::new(&resolve);
+    let packages = ids
+        .iter()
+        .map(|&id| encodable_resolve_node(id, &resolve, &state))
+        .collect::<Vec<_>>();
+    let metadata = resolve.metadata().clone();
+
+    let version = match resolve.version() {
+        ResolveVersion::V5 => 5,
+        ResolveVersion::V4 => 4,
+        ResolveVersion::V3 => 3,
+        ResolveVersion::V2 => 2,
+        ResolveVersion::V1 => 1,
+    };
+    let mut out = vec![LockDependenciesOut::Lockfile { version, partial }];
+
+    for package in normalize_packages(None, Some(packages), Some(metadata), None)? {
+        out.push(LockDependenciesOut::LockedPackage { package });
+    }
+
+    let unused: Vec<_> = resolve
+        .unused_patches()
+        .iter()
+        .map(|id| {
+            normalize_dependency(TomlLockfileDependency {
+                name: id.name().to_string(),
+                version: id.version().to_string(),
+                source: encodable_source_id(id.source_id(), resolve.version()),
+                dependencies: None,
+                replace: None,
+                checksum: resolve.checksums().get(id).and_then(|x| x.clone()),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if opts.report_rust_versions {
+        let rust_version = match &opts.rust_version {
+            Some(rust_version) => rust_version.as_partial().clone(),
+            None => honored_rust_version(ws)?,
+        };
+        let fallback = opts.rust_version.is_some() || ws.resolve_honors_rust_version();
+        let previous_ids = previous_resolve
+            .iter()
+            .flat_map(|previous| previous.iter())
+            .collect::<HashSet<_>>();
+        out.extend(rust_version_report(
+            &mut registry,
+            &resolve,
+            &ids,
+            &rust_version,
+            fallback.then_some(&previous_ids),
+        )?);
+    }
+    if opts.report_yanked {
+        out.extend(yanked_report(&mut registry, &ids)?);
+    }
+    if opts.report_source_replacements {
+        let source_ids = ids.iter().map(|id| id.source_id());
+        for (source, replace_with) in source_replacements(gctx, source_ids)? {
+            out.push(LockDependenciesOut::SourceReplacement {
+                source: source.as_url().to_string(),
+                replace_with: replace_with.as_url().to_string(),
+            });
+        }
+    }
+
+    if opts.report_patches {
+        out.extend(patch_report(ws, &resolve, &ids)?);
+    }
+
+    if !unused.is_empty() {
+        let unused = NormalizedPatch { unused };
+        out.push(LockDependenciesOut::UnusedPatches { unused });
+    }
+
+    Ok(out)
+}
+
+/// Compares the locked registry packages against `rust_version`.
+///
+/// The newer versions skipped for it are only reported when the resolver fell back to versions
+/// supporting it, leaving out the packages kept at their version in the previous resolve, which
+/// `fallback` holds the ids of.
+fn rust_version_report(
+    registry: &mut PackageRegistry<'_>,
+    resolve: &Resolve,
+    ids: &[PackageId],
+    rust_version: &PartialVersion,
+    fallback: Option<&HashSet<PackageId>>,
+) -> CargoResult<Vec<LockDependenciesOut>> {
+    let mut out = Vec::new();
+    for &id in ids.iter().filter(|id| id.source_id().is_registry()) {
+        let candidates = allowed_versions(registry, resolve, id)?;
+        let Some(locked) = candidates.iter().find(|summary| summary.package_id() == id) else {
+            continue;
+        };
+        if let Some(locked_rust_version) = incompatible_rust_version(locked, rust_version) {
+            let compatible_version = candidates
+                .iter()
+                .find(|summary| incompatible_rust_version(summary, rust_version).is_none())
+                .map(|summary| summary.version().to_string());
+            out.push(LockDependenciesOut::RustVersionIncompatible {
+                id: id.to_spec(),
+                rust_version: locked_rust_version.to_string(),
+                compatible_version,
+            });
+            continue;
+        }
+
+        let Some(previous_ids) = fallback else {
+            continue;
+        };
+        if previous_ids.contains(&id) {
+            continue;
+        }
+        if let Some((skipped, skipped_rust_version)) = candidates
+            .iter()
+            .filter(|summary| summary.version() > id.version())
+            .find_map(|summary| Some((summary, incompatible_rust_version(summary, rust_version)?)))
+        {
+            out.push(LockDependenciesOut::RustVersionSkipped {
+                id: id.to_spec(),
+                version: skipped.version().to_string(),
+                rust_version: skipped_rust_version.to_string(),
+            });
+        }
+    }
+
+    Ok(out)
+}
+
+/// The locked registry packages in `resolve` requiring a newer Rust than `rust_version`, with the
+/// newest version their dependents allow that supports it.
+fn rust_version_fallbacks(
+    registry: &mut PackageRegistry<'_>,
+    resolve: &Resolve,
+    rust_version: &RustVersion,
+) -> CargoResult<Vec<(PackageId, PackageId)>> {
+    let rust_version = rust_version.as_partial();
+
+    let mut fallbacks = Vec::new();
+    for id in resolve.iter().filter(|id| id.source_id().is_registry()) {
+        let candidates = allowed_versions(registry, resolve, id)?;
+        let Some(locked) = candidates.iter().find(|summary| summary.package_id() == id) else {
+            continue;
+        };
+        if incompatible_rust_version(locked, rust_version).is_none() {
+            continue;
+        }
+        if let Some(compatible) = candidates
+            .iter()
+            .find(|summary| incompatible_rust_version(summary, rust_version).is_none())
+        {
+            fallbacks.push((id, compatible.package_id()));
+        }
+    }
+
+    Ok(fallbacks)
+}
+
+/// Copies `resolve` with the packages in `fallbacks` swapped, to resolve again with it as the
+/// previous resolve.
+///
+/// The swapped packages are left without dependencies, so the resolver locks theirs anew.
+fn pin_fallbacks(resolve: &Resolve, fallbacks: &HashMap<PackageId, PackageId>) -> Resolve {
+    let swap = |id: PackageId| fallbacks.get(&id).copied().unwrap_or(id);
+
+    let mut graph = Graph::new();
+    for id in resolve.iter() {
+        graph.add(swap(id));
+    }
+    for id in resolve.iter().filter(|id| !fallbacks.contains_key(id)) {
+        for (dep_id, _) in resolve.deps_not_replaced(id) {
+            graph.link(id, swap(dep_id));
+        }
+    }
+
+    let replacements = resolve
+        .replacements()
+        .iter()
+        .filter(|(id, _)| !fallbacks.contains_key(id))
+        .map(|(&id, &replace_id)| (id, replace_id))
+        .collect();
+    let checksums = resolve
+        .checksums()
+        .iter()
+        .filter(|(id, _)| !fallbacks.contains_key(id))
+        .map(|(&id, checksum)| (id, checksum.clone()))
+        .collect();
+
+    Resolve::new(
+        graph,
+        replacements,
+        HashMap::new(),
+        checksums,
+        resolve.metadata().clone(),
+        resolve.unused_patches().to_vec(),
+        resolve.version(),
+        HashMap::new(),
+    )
+}
+
+/// The versions of `id` that all the dependencies in `resolve` resolved to it allow, newest
+/// first, leaving out yanked ones.
+fn allowed_versions(
+    registry: &mut PackageRegistry<'_>,
+    resolve: &Resolve,
+    id: PackageId,
+) -> CargoResult<Vec<Summary>> {
+    let reqs = resolve
+        .iter()
+        .flat_map(|parent| resolve.deps(parent))
+        .filter(|&(dep_id, _)| dep_id == id)
+        .flat_map(|(_, deps)| deps.iter())
+        .collect::<Vec<_>>();
+
+    let dep = Dependency::parse(id.name(), None, id.source_id())?;
+    let mut candidates = query(registry, &dep)?
+        .into_iter()
+        .filter(|summary| !summary.is_yanked())
+        .map(|summary| summary.into_summary())
+        .filter(|summary| reqs.iter().all(|req| allows(req, summary.version())))
+        .collect::<Vec<_>>();
+    candidates.sort_by(|a, b| b.version().cmp(a.version()));
+    Ok(candidates)
+}
+
+/// Whether the requirement of `dep` allows `version`, regardless of the version it was locked to.
+fn allows(dep: &Dependency, version: &semver::Version) -> bool {
+    match dep.version_req() {
+        OptVersionReq::Locked(_, req) | OptVersionReq::Precise(_, req) => req.matches(version),
+        req => req.matches(version),
+    }
+}
+
+/// Looks up each locked registry package in its index to find the ones that were yanked since
+/// they were locked.
+fn yanked_report(
+    registry: &mut PackageRegistry<'_>,
+    ids: &[PackageId],
+) -> CargoResult<Vec<LockDependenciesOut>> {
+    let ids = ids
+        .iter()
+        .copied()
+        .filter(|id| id.source_id().is_registry())
+        .collect::<Vec<_>>();
+    // Registries leave out yanked versions unless they are allowed to be locked.
+    registry.add_to_yanked_whitelist(ids.iter().copied());
+
+    let mut out = Vec::new();
+    for id in ids {
+        let dep = Dependency::parse(
+            id.name(),
+            Some(&format!("={}", id.version())),
+            id.source_id(),
+        )?;
+        let yanked = query(registry, &dep)?
+            .iter()
+            .any(|summary| summary.package_id() == id && summary.is_yanked());
+        if yanked {
+            out.push(LockDependenciesOut::Yanked { id: id.to_spec() });
+        }
+    }
+
+    Ok(out)
+}
+
+/// Describes each `[patch]` the resolve used and each `[replace]` it applied, along with the
+/// dependencies they override.
+fn patch_report(
+    ws: &Workspace<'_>,
+    resolve: &Resolve,
+    ids: &[PackageId],
+) -> CargoResult<Vec<LockDependenciesOut>> {
+    let root_manifest = ws.root_manifest();
+    let manifest_patches = manifest_patches(ws)?;
+
+    let mut out = Vec::new();
+    let mut patches = ws.root_patch()?.into_iter().collect::<Vec<_>>();
+    patches.sort_by(|(a, _), (b, _)| a.cmp(b));
+    for (url, deps) in patches {
+        let url = CanonicalUrl::new(&url)?;
+        for dep in deps {
+            // Unused patches are already reported as such.
+            let Some(&id) = ids.iter().find(|&&id| dep.matches_id(id)) else {
+                continue;
+            };
+            let declared_in_manifest = manifest_patches
+                .get(&url)
+                .is_some_and(|names| names.contains(dep.name_in_toml().as_str()));
+            out.push(LockDependenciesOut::Patch {
+                source: url.raw_canonicalized_url().to_string(),
+                id: id.to_spec(),
+                patch_source: id.source_id().as_url().to_string(),
+                dependencies: overridden_dependencies(resolve, id, |dep| {
+                    dep.source_id().canonical_url() == &url
+                }),
+                manifest_path: declared_in_manifest.then(|| root_manifest.to_owned()),
+            });
+        }
+    }
+
+    let mut replacements = resolve.replacements().iter().collect::<Vec<_>>();
+    replacements.sort();
+    for (&id, &replace_with) in replacements {
+        out.push(LockDependenciesOut::Replace {
+            id: id.to_spec(),
+            replace_with: replace_with.to_spec(),
+            dependencies: overridden_dependencies(resolve, id, |_| true),
+            manifest_path: root_manifest.to_owned(),
+        });
+    }
+
+    Ok(out)
+}
+
+/// The names patched by the `[patch]` tables of the root manifest, by the URL they patch.
+///
+/// Patches from the Cargo configuration are merged into [`Workspace::root_patch`], so this tells
+/// them apart.
+fn manifest_patches(ws: &Workspace<'_>) -> CargoResult<HashMap<CanonicalUrl, HashSet<String>>> {
+    let toml = match ws.root_maybe() {
+        MaybePackage::Package(pkg) => pkg.manifest().normalized_toml(),
+        MaybePackage::Virtual(v) => v.normalized_toml(),
+    };
+
+    let mut patches = HashMap::new();
+    for (key, deps) in toml.patch.iter().flatten() {
+        // Like Cargo, the key is either `crates-io`, the name of a registry or a URL.
+        let url = match key.as_str() {
+            CRATES_IO_REGISTRY => Url::parse(CRATES_IO_INDEX)?,
+            _ => ws
+                .gctx()
+                .get_registry_index(key)
+                .or_else(|_| Url::parse(key).map_err(anyhow::Error::from))?,
+        };
+        patches
+            .entry(CanonicalUrl::new(&url)?)
+            .or_default()
+            .extend(deps.keys().map(|name| name.to_string()));
+    }
+    Ok(patches)
+}
+
+/// The dependencies in `resolve` resolved to `id` that `filter` accepts, with the packages
+/// declaring them.
+fn overridden_dependencies(
+    resolve: &Resolve,
+    id: PackageId,
+    filter: impl Fn(&Dependency) -> bool,
+) -> Vec<OverriddenDependency> {
+    let mut dependencies = Vec::new();
+    for parent in resolve.iter() {
+        for (dep_id, deps) in resolve.deps(parent) {
+            if dep_id != id {
+                continue;
+            }
+            for dep in deps.iter().filter(|dep| filter(dep)) {
+                dependencies.push((parent, dep.version_req().to_string()));
+            }
+        }
+    }
+    dependencies.sort();
+    dependencies.dedup();
+
+    dependencies
+        .into_iter()
+        .map(|(package, req)| OverriddenDependency {
+            package: package.to_spec(),
+            req,
+        })
+        .collect()
+}
+
+/// The `rust-version` the resolver honors, which is the lowest one among the members or else the
+/// version of `rustc`.
+fn honored_rust_version(ws: &Workspace<'_>) -> CargoResult<PartialVersion> {
+    let rust_version = match ws.lowest_rust_version() {
+        Some(rust_version) => rust_version.as_partial().clone(),
+        None => ws
+            .gctx()
+            .load_global_rustc(Some(ws))?
+            .version
+            .clone()
+            .into(),
+    };
+    Ok(rust_version)
+}
+
+/// The `rust-version` of `summary`, if it is newer than `rust_version`.
+fn incompatible_rust_version<'s>(
+    summary: &'s Summary,
+    rust_version: &PartialVersion,
+) -> Option<&'s RustVersion> {
+    summary
+        .rust_version()
+        .filter(|rv| !rv.is_compatible_with(rust_version))
+}
+
+/// The part of `version` a caret requirement keeps fixed, so versions in the same series are
+/// semver-compatible.
+fn semver_series(version: &semver::Version) -> (u64, u64, u64) {
+    match (version.major, version.minor) {
+        (0, 0) => (0, 0, version.patch),
+        (0, minor) => (0, minor, 0),
+        (major, _) => (major, 0, 0),
+    }
+}
+
+/// Describes `err` as a [`LockDependenciesOut::ResolveError`] if [`lock_dependencies`] failed
+/// because the dependencies couldn't be resolved.
+///
+/// The requirements and candidates are looked up again from the sources of the dependencies.
+/// They are left empty if that fails, so the original error is still described.
+pub fn resolve_error(ws: &Workspace<'_>, err: &anyhow::Error) -> Option<LockDependenciesOut> {
+    let resolve_err = err
+        .chain()
+        .find_map(|err| err.downcast_ref::<ResolveError>())?;
+    let package_path = resolve_err.package_path();
+
+    let (requirements, candidates) = match package_path.first() {
+        Some(&failed) => explain_resolve_error(ws, failed).unwrap_or_default(),
+        None => Default::default(),
+    };
+
+    Some(LockDependenciesOut::ResolveError {
+        message: resolve_err.to_string(),
+        package_path: package_path.iter().map(|id| id.to_spec()).collect(),
+        requirements,
+        candidates,
+        offline: ws.gctx().offline(),
+    })
+}
+
+/// A dependency along with the package declaring it
+type Requirement = (Dependency, PackageId);
+
+/// Finds the requirements among the dependencies of `failed` that couldn't be satisfied, and the
+/// versions that were available for them.
+///
+/// A requirement can't be satisfied either because no usable version matches it, or because it
+/// collides with a requirement on the same package elsewhere in the dependency graph: both want
+/// versions from the same semver-compatible series, which Cargo only allows one of, but no
+/// version matches both.
+fn explain_resolve_error(
+    ws: &Workspace<'_>,
+    failed: PackageId,
+) -> CargoResult<(Vec<ResolveRequirement>, Vec<ResolveCandidate>)> {
+    let gctx = ws.gctx();
+    let mut registry = PackageRegistry::new_with_source_config(gctx, SourceConfigMap::new(gctx)?)?;
+    registry.lock_patches();
+    let mut candidates = Candidates {
+        registry,
+        members: ws.members().map(|pkg| pkg.summary().clone()).collect(),
+        summaries: HashMap::new(),
+    };
+
+    let Some(parent) = candidates.summary(failed)? else {
+        return Ok(Default::default());
+    };
+    let is_member = candidates.is_member(failed);
+    let deps = parent
+        .dependencies()
+        .iter()
+        .filter(|dep| is_member || dep.kind() != DepKind::Development)
+        .collect::<Vec<_>>();
+
+    let mut failing: Vec<Vec<Requirement>> = Vec::new();
+    for &dep in &deps {
+        if candidates.usable(dep)?.is_empty() {
+            failing.push(vec![(dep.clone(), failed)]);
+        }
+    }
+    if failing.is_empty() {
+        let graph = graph_requirements(&mut candidates)?;
+        for &dep in &deps {
+            let usable = candidates.usable(dep)?;
+            let series = usable
+                .iter()
+                .map(|summary| semver_series(summary.version()))
+                .collect::<HashSet<_>>();
+
+            let mut group = vec![(dep.clone(), failed)];
+            let key = (dep.package_name(), dep.source_id());
+            for (other, package) in graph.get(&key).into_iter().flatten() {
+                if *package == failed {
+                    continue;
+                }
+                let other_usable = candidates.usable(other)?;
+                let same_series = other_usable
+                    .iter()
+                    .any(|summary| series.contains(&semver_series(summary.version())));
+                let shared = other_usable.iter().any(|summary| dep.matches(summary));
+                if same_series && !shared {
+                    group.push((other.clone(), *package));
+                }
+            }
+            if group.len() > 1 {
+                failing.push(group);
+            }
+        }
+    }
+
+    let rust_version = if ws.resolve_honors_rust_version() {
+        Some(honored_rust_version(ws)?)
+    } else {
+        None
+    };
+
+    let mut requirements = Vec::new();
+    let mut versions = Vec::new();
+    for group in &failing {
+        for (dep, package) in group {
+            requirements.push(ResolveRequirement {
+                name: dep.package_name().to_string(),
+                req: dep.version_req().to_string(),
+                package: package.to_spec(),
+            });
+        }
+
+        let (dep, _) = &group[0];
+        let mut summaries = candidates.all(dep)?.iter().collect::<Vec<_>>();
+        summaries.sort_by_key(|summary| summary.as_summary().package_id());
+        for summary in summaries {
+            versions.push(ResolveCandidate {
+                id: summary.as_summary().package_id().to_spec(),
+                rejected: rejected_reason(summary, group, rust_version.as_ref()),
+            });
+        }
+    }
+
+    Ok((requirements, versions))
+}
+
+/// Collects the requirements on each package, by their name and source, walking the dependency
+/// graph from the workspace members through the newest usable version of each dependency, the
+/// one the resolver tries first.
+///
+/// Optional dependencies are only followed for the members, which are resolved with all of their
+/// features.
+fn graph_requirements(
+    candidates: &mut Candidates<'_>,
+) -> CargoResult<HashMap<(InternedString, SourceId), Vec<Requirement>>> {
+    let mut requirements: HashMap<_, Vec<_>> = HashMap::new();
+    let mut visited = HashSet::new();
+    let mut queue = candidates.members.iter().cloned().collect::<VecDeque<_>>();
+    while let Some(summary) = queue.pop_front() {
+        let id = summary.package_id();
+        if !visited.insert(id) {
+            continue;
+        }
+        let is_member = candidates.is_member(id);
+        for dep in summary.dependencies() {
+            if !is_member && (dep.is_optional() || dep.kind() == DepKind::Development) {
+                continue;
+            }
+            requirements
+                .entry((dep.package_name(), dep.source_id()))
+                .or_default()
+                .push((dep.clone(), id));
+            if let Some(next) = candidates.usable(dep)?.into_iter().next() {
+                queue.push_back(next);
+            }
+        }
+    }
+    Ok(requirements)
+}
+
+/// Why `summary` couldn't be used for all of `requirements` on its package.
+fn rejected_reason(
+    summary: &IndexSummary,
+    requirements: &[Requirement],
+    rust_version: Option<&PartialVersion>,
+) -> Option<RejectedReason> {
+    if summary.is_yanked() {
+        Some(RejectedReason::Yanked)
+    } else if matches!(summary, IndexSummary::Offline(_)) {
+        Some(RejectedReason::Offline)
+    } else if matches!(summary, IndexSummary::Unsupported(..)) {
+        Some(RejectedReason::Unsupported)
+    } else if !requirements
+        .iter()
+        .all(|(dep, _)| dep.matches(summary.as_summary()))
+    {
+        Some(RejectedReason::Unmatched)
+    } else if rust_version
+        .and_then(|rust_version| incompatible_rust_version(summary.as_summary(), rust_version))
+        .is_some()
+    {
+        Some(RejectedReason::RustVersion)
+    } else {
+        None
+    }
+}
+
+/// Looks up the versions of packages, caching them by package name and source
+struct Candidates<'gctx> {
+    registry: PackageRegistry<'gctx>,
+    members: Vec<Summary>,
+    summaries: HashMap<(InternedString, SourceId), Vec<IndexSummary>>,
+}
+
+impl Candidates<'_> {
+    fn is_member(&self, id: PackageId) -> bool {
+        self.members.iter().any(|member| member.package_id() == id)
+    }
+
+    /// The summary of `id`, even if it was yanked.
+    fn summary(&mut self, id: PackageId) -> CargoResult<Option<Summary>> {
+        let dep = Dependency::parse(
+            id.name(),
+            Some(&format!("={}", id.version())),
+            id.source_id(),
+        )?;
+        Ok(self
+            .all(&dep)?
+            .iter()
+            .map(|summary| summary.as_summary())
+            .find(|summary| summary.package_id() == id)
+            .cloned())
+    }
+
+    /// Every version of the package `dep` depends on, including the ones a resolve rejects.
+    fn all(&mut self, dep: &Dependency) -> CargoResult<&[IndexSummary]> {
+        let key = (dep.package_name(), dep.source_id());
+        if !self.summaries.contains_key(&key) {
+            let summaries = match self
+                .members
+                .iter()
+                .find(|member| dep.matches_id(member.package_id()))
+            {
+                Some(member) => vec![IndexSummary::Candidate(member.clone())],
+                None => {
+                    let any = Dependency::parse(dep.package_name(), None, dep.source_id())?;
+                    query_rejected(&mut self.registry, &any)?
+                }
+            };
+            self.summaries.insert(key, summaries);
+        }
+        Ok(&self.summaries[&key])
+    }
+
+    /// The versions that can be used for `dep`, newest first.
+    fn usable(&mut self, dep: &Dependency) -> CargoResult<Vec<Summary>> {
+        let mut usable = self
+            .all(dep)?
+            .iter()
+            .filter(|summary| matches!(summary, IndexSummary::Candidate(_)))
+            .map(|summary| summary.as_summary())
+            .filter(|summary| dep.matches(summary))
+            .cloned()
+            .collect::<Vec<_>>();
+        usable.sort_by(|a, b| b.version().cmp(a.version()));
+        Ok(usable)
+    }
+}
@@ -1 +1,98 @@
+//! In-process implementations of the plumbing commands
+//!
+//! Each plumbing command has a corresponding function here taking and returning the messages
+//! defined in [`cargo_plumbing_schemas`]. The `cargo-plumbing` binary is a thin wrapper around
+//! these functions, reading input messages from stdin and printing output messages to stdout.
+//! Tools may call these directly to chain commands without spawning processes.
+
+use std::fmt;
+use std::path::PathBuf;
+
+use cargo::core::manifest::TargetKind;
+use cargo::core::Workspace;
+use cargo::util::context::ConfigRelativePath;
+use cargo::CargoResult;
+use cargo_plumbing_schemas::MESSAGE_FORMAT_VERSION;
+use clap::ValueEnum;
+
+pub mod locate_manifest;
+pub mod lock_dependencies;
+pub mod plan_build;
+pub mod query_index;
+pub mod read_lockfile;
+pub mod read_manifest;
 pub mod resolve;
+pub mod resolve_features;
+pub mod validate;
+pub mod vendor;
+pub mod write_lockfile;
+
+pub use self::locate_manifest::{locate_manifest, LocateManifestOptions};
+pub use self::lock_dependencies::{lock_dependencies, LockDependenciesOptions};
+pub use self::plan_build::{plan_build, PlanBuildOptions};
+pub use self::query_index::{query_index, QueryIndexOptions};
+pub use self::read_lockfile::{read_lockfile, ReadLockfileOptions};
+pub use self::read_manifest::{read_dependency_manifests, read_manifest, ReadManifestOptions};
+pub use self::resolve_features::{resolve_features, ResolveFeaturesOptions};
+pub use self::validate::validate;
+pub use self::vendor::vendor;
+pub use self::write_lockfile::write_lockfile;
+
+/// The plumbing commands exchanging messages, as named on the command line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Command {
+    LocateManifest,
+    ReadLockfile,
+    ReadManifest,
+    LockDependencies,
+    WriteLockfile,
+    ResolveFeatures,
+    PlanBuild,
+    QueryIndex,
+    Validate,
+    Vendor,
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let value = self.to_possible_value().expect("no variant is skipped");
+        f.write_str(value.get_name())
+    }
+}
+
+/// Checks that `version` is a message format version supported by this crate.
+pub fn check_message_format_version(version: u32) -> CargoResult<()> {
+    if version != MESSAGE_FORMAT_VERSION {
+        anyhow::bail!(
+            "unsupported message format version `{version}`, \
+            supported versions: {MESSAGE_FORMAT_VERSION}"
+        );
+    }
+    Ok(())
+}
+
+/// The path to the lockfile of `ws`.
+///
+/// This honors the `resolver.lockfile-path` config when `-Zlockfile-path` is enabled, and
+/// single-file packages keep their lockfile along with their build output.
+pub fn lockfile_path(ws: &Workspace<'_>) -> CargoResult<PathBuf> {
+    let gctx = ws.gctx();
+    if gctx.cli_unstable().lockfile_path {
+        if let Some(path) = gctx.get::<Option<ConfigRelativePath>>("resolver.lockfile-path")? {
+            return Ok(path.resolve_path(gctx));
+        }
+    }
+    Ok(ws.lock_root().as_path_unlocked().join("Cargo.lock"))
+}
+
+/// Describes a target of `kind` the way the `kind` of `cargo metadata` targets does.
+pub(crate) fn target_kind_names(kind: &TargetKind) -> Vec<String> {
+    match kind {
+        TargetKind::Lib(crate_types) => crate_types.iter().map(|ct| ct.to_string()).collect(),
+        TargetKind::Bin => vec!["bin".to_owned()],
+        TargetKind::ExampleBin | TargetKind::ExampleLib(_) => vec!["example".to_owned()],
+        TargetKind::Test => vec!["test".to_owned()],
+        TargetKind::Bench => vec!["bench".to_owned()],
+        TargetKind::CustomBuild => vec!["custom-build".to_owned()],
+    }
+}
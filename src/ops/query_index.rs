@@ -0,0 +1,111 @@
+use cargo::core::dependency::DepKind;
+use cargo::core::registry::PackageRegistry;
+use cargo::core::{Dependency, SourceId};
+use cargo::sources::{IndexSummary, SourceConfigMap};
+use cargo::util::cache_lock::CacheLockMode;
+use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing_schemas::query_index::{IndexDependency, QueryIndexOut};
+
+use crate::ops::resolve::query_rejected;
+
+/// Options for [`query_index`]
+#[derive(Debug, Default)]
+pub struct QueryIndexOptions {
+    /// Name of the registry to query instead of crates.io
+    pub registry: Option<String>,
+}
+
+/// Queries the registry index for the versions of `packages`, each given as `NAME` or
+/// `NAME@VERSION_REQ`.
+///
+/// The registry is looked up through the configured source replacements, and only the locally
+/// cached index is used when offline. Yanked versions are listed as well, flagged as such.
+pub fn query_index(
+    gctx: &GlobalContext,
+    opts: &QueryIndexOptions,
+    packages: &[String],
+) -> CargoResult<Vec<QueryIndexOut>> {
+    let source_id = match &opts.registry {
+        Some(registry) => SourceId::alt_registry(gctx, registry)?,
+        None => SourceId::crates_io(gctx)?,
+    };
+
+    let _lock = gctx.acquire_package_cache_lock(CacheLockMode::DownloadExclusive)?;
+    let source_config = SourceConfigMap::new(gctx)?;
+    let mut registry = PackageRegistry::new_with_source_config(gctx, source_config)?;
+    registry.lock_patches();
+
+    let mut out = Vec::new();
+    for package in packages {
+        let (name, req) = match package.split_once('@') {
+            Some((name, req)) => (name, Some(req)),
+            None => (package.as_str(), None),
+        };
+        let dep = Dependency::parse(name, req, source_id)?;
+
+        // Registries leave out yanked versions unless the query asks for the rejected ones too,
+        // which also returns the versions this Cargo can't use.
+        let mut summaries = query_rejected(&mut registry, &dep)?
+            .into_iter()
+            .filter(|summary| {
+                matches!(
+                    summary,
+                    IndexSummary::Candidate(_) | IndexSummary::Yanked(_)
+                )
+            })
+            .collect::<Vec<_>>();
+        if summaries.is_empty() {
+            anyhow::bail!(
+                "could not find `{package}` in registry `{}`",
+                source_id.display_registry_name()
+            );
+        }
+        summaries.sort_by(|a, b| a.as_summary().version().cmp(b.as_summary().version()));
+
+        for summary in summaries {
+            let yanked = summary.is_yanked();
+            let summary = summary.as_summary();
+            let features = summary
+                .features()
+                .iter()
+                .map(|(feature, values)| {
+                    let values = values.iter().map(|value| value.to_string()).collect();
+                    (feature.to_string(), values)
+                })
+                .collect();
+            let dependencies = summary
+                .dependencies()
+                .iter()
+                .map(index_dependency)
+                .collect();
+
+            out.push(QueryIndexOut::IndexEntry {
+                id: summary.package_id().to_spec(),
+                features,
+                dependencies,
+                rust_version: summary.rust_version().map(|rv| rv.to_string()),
+                yanked,
+                checksum: summary.checksum().map(|checksum| checksum.to_owned()),
+            });
+        }
+    }
+
+    Ok(out)
+}
+
+fn index_dependency(dep: &Dependency) -> IndexDependency {
+    IndexDependency {
+        name: dep.name_in_toml().to_string(),
+        package: (dep.name_in_toml() != dep.package_name()).then(|| dep.package_name().to_string()),
+        req: dep.version_req().to_string(),
+        kind: match dep.kind() {
+            DepKind::Normal => None,
+            DepKind::Development => Some("dev".to_owned()),
+            DepKind::Build => Some("build".to_owned()),
+        },
+        optional: dep.is_optional(),
+        default_features: dep.uses_default_features(),
+        features: dep.features().iter().map(|f| f.to_string()).collect(),
+        target: dep.platform().map(|platform| platform.to_string()),
+    }
+}
@@ -0,0 +1,70 @@
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::Context as _;
+use cargo::util::Filesystem;
+use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing_schemas::read_lockfile::ReadLockfileOut;
+use cargo_util_schemas::lockfile::TomlLockfile;
+
+use crate::ops::resolve::{normalize_resolve, source_replacements, spec_to_id};
+
+/// Options for [`read_lockfile`]
+#[derive(Debug, Default)]
+pub struct ReadLockfileOptions {
+    /// Report the sources of locked packages replaced by `[source]` tables
+    pub report_source_replacements: bool,
+}
+
+/// Reads and normalizes the lockfile at `lockfile_path`.
+pub fn read_lockfile(
+    gctx: &GlobalContext,
+    lockfile_path: &Path,
+    opts: &ReadLockfileOptions,
+) -> CargoResult<Vec<ReadLockfileOut>> {
+    let lock_path = gctx.cwd().join(lockfile_path);
+    let file_name = lock_path
+        .file_name()
+        .with_context(|| format!("lockfile path `{}` has no file name", lock_path.display()))?;
+
+    let root = lock_path.parent().expect("Lockfile path can't be root");
+    let lock_root = Filesystem::new(root.to_owned());
+
+    let mut lock_f = lock_root.open_ro_shared(file_name, gctx, "lockfile")?;
+    let mut lock_s = String::new();
+    lock_f
+        .read_to_string(&mut lock_s)
+        .with_context(|| format!("failed to read file: {}", lock_f.path().display()))?;
+
+    let v: TomlLockfile = toml::from_str(&lock_s)?;
+    let n = normalize_resolve(v)?;
+
+    let replacements = if opts.report_source_replacements {
+        // Path dependencies have no source in the lockfile, so they are left out here.
+        let source_ids = n
+            .package
+            .iter()
+            .filter_map(|pkg| spec_to_id(pkg.id.clone(), None, pkg.rev.clone()).transpose())
+            .map(|id| id.map(|id| id.source_id()))
+            .collect::<CargoResult<Vec<_>>>()?;
+        source_replacements(gctx, source_ids)?
+    } else {
+        Vec::new()
+    };
+
+    let mut out = vec![ReadLockfileOut::Lockfile { version: n.version }];
+    for package in n.package {
+        out.push(ReadLockfileOut::LockedPackage { package });
+    }
+    for (source, replace_with) in replacements {
+        out.push(ReadLockfileOut::SourceReplacement {
+            source: source.as_url().to_string(),
+            replace_with: replace_with.as_url().to_string(),
+        });
+    }
+    if !n.patch.is_empty() {
+        out.push(ReadLockfileOut::UnusedPatches { unused: n.patch });
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,567 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+use std::path::Path;
+
+use anyhow::Context as _;
+use camino::Utf8PathBuf;
+use cargo::core::manifest::{Target, Warnings};
+use cargo::core::{
+    find_workspace_root, EitherManifest, Manifest, MaybePackage, PackageId, PackageIdSpec,
+    SourceId, VirtualManifest, Workspace, WorkspaceConfig,
+};
+use cargo::ops::get_resolved_packages;
+use cargo::util::toml::read_manifest as cargo_read_manifest;
+use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing_schemas::read_manifest::{
+    ManifestSpan, ManifestTarget, ReadManifestIn, ReadManifestOut, Span, WorkspaceMember,
+};
+use cargo_util::paths;
+use cargo_util_schemas::manifest::TomlManifest;
+use toml::de::{DeTable, DeValue};
+use toml::Spanned;
+
+use crate::ops::resolve::into_resolve;
+use crate::ops::{check_message_format_version, target_kind_names};
+
+/// Options for [`read_manifest`] and [`read_dependency_manifests`]
+#[derive(Debug, Default)]
+pub struct ReadManifestOptions {
+    /// Read all manifests in the workspace
+    pub workspace: bool,
+    /// Include each manifest as written, before workspace inheritance and target inference
+    pub original: bool,
+    /// Include where each table and key is written in the manifest files
+    pub spans: bool,
+    /// Include the targets of each package, including the ones discovered from its files
+    pub targets: bool,
+    /// Report the warnings about each manifest, such as unused keys
+    pub report_warnings: bool,
+    /// Report the members of the workspace and why each of them is one
+    ///
+    /// Only used along with [`ReadManifestOptions::workspace`].
+    pub report_workspace: bool,
+}
+
+/// Reads the manifest at `manifest_path`, along with its workspace manifest.
+///
+/// With [`ReadManifestOptions::workspace`], every manifest in the workspace is read instead.
+pub fn read_manifest(
+    gctx: &GlobalContext,
+    manifest_path: &Path,
+    opts: &ReadManifestOptions,
+) -> CargoResult<Vec<ReadManifestOut>> {
+    let requested_manifest_path = gctx.cwd().join(manifest_path);
+    let mut out = Vec::new();
+
+    if opts.workspace {
+        let workspace = Workspace::new(&requested_manifest_path, gctx)?;
+
+        // Here, we output the root Package or the Virtual Manifest of the workspace.
+        let (path, manifest) = match workspace.root_maybe() {
+            MaybePackage::Package(pkg) => (pkg.manifest_path(), ManifestRef::Real(pkg.manifest())),
+            MaybePackage::Virtual(v) => (workspace.root_manifest(), ManifestRef::Virtual(v)),
+        };
+        out.extend(manifest_messages(
+            opts,
+            true,
+            &gctx.cwd().join(path),
+            manifest,
+        )?);
+
+        // We don't want to output the same manifest twice. This is why we're filtering the
+        // workspace members to make sure only those that are not root manifest are output in this
+        // stage.
+        for member in workspace
+            .members()
+            .filter(|p| p.manifest_path() != workspace.root_manifest())
+        {
+            out.extend(manifest_messages(
+                opts,
+                false,
+                &gctx.cwd().join(member.manifest_path()),
+                ManifestRef::Real(member.manifest()),
+            )?);
+        }
+
+        if opts.report_workspace {
+            out.push(workspace_message(gctx, &workspace)?);
+        }
+    } else {
+        // As this is the branch without `workspace`, we want to only read one manifest.
+        let source_id = SourceId::for_manifest_path(&requested_manifest_path)?;
+        let manifest = cargo_read_manifest(&requested_manifest_path, source_id, gctx)?;
+        let ws_config = match &manifest {
+            EitherManifest::Real(r) => r.workspace_config(),
+            EitherManifest::Virtual(v) => v.workspace_config(),
+        };
+
+        // If the current manifest is a workspace member, find its workspace manifest path.
+        let ws_manifest_path = match ws_config {
+            WorkspaceConfig::Root(..) => {
+                // The current manifest is already the workspace root, so there is no other
+                // workspace root to find.
+                None
+            }
+            WorkspaceConfig::Member {
+                root: Some(path_to_root),
+            } => {
+                // The member's `[package.workspace]` key points to the root directory
+                Some(
+                    requested_manifest_path
+                        .parent()
+                        .context("manifest-path can't be root")?
+                        .join(path_to_root)
+                        .join("Cargo.toml"),
+                )
+            }
+            WorkspaceConfig::Member { root: None } => {
+                // Find the root directory by searching upwards the filesystem.
+                find_workspace_root(&requested_manifest_path, gctx)?
+            }
+        };
+
+        out.extend(manifest_messages(
+            opts,
+            ws_manifest_path.is_none(),
+            &requested_manifest_path,
+            ManifestRef::from(&manifest),
+        )?);
+
+        if let Some(ws_manifest_path) = ws_manifest_path {
+            let ws_manifest_path = paths::normalize_path(&gctx.cwd().join(ws_manifest_path));
+            let source_id = SourceId::for_manifest_path(&ws_manifest_path)?;
+
+            let ws_manifest = cargo_read_manifest(&ws_manifest_path, source_id, gctx)?;
+            out.extend(manifest_messages(
+                opts,
+                true,
+                &ws_manifest_path,
+                ManifestRef::from(&ws_manifest),
+            )?);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Reads the manifests of the locked packages in `input` that aren't members of `ws`.
+///
+/// The manifests are read from where Cargo keeps the sources of each package: path dependencies
+/// in place, git dependencies from their checkout and registry dependencies from the registry
+/// cache, downloading them if needed.
+pub fn read_dependency_manifests(
+    ws: &Workspace<'_>,
+    opts: &ReadManifestOptions,
+    input: impl IntoIterator<Item = ReadManifestIn>,
+) -> CargoResult<Vec<ReadManifestOut>> {
+    let gctx = ws.gctx();
+
+    let mut locked_packages = Vec::new();
+    let mut unused_patches = None;
+
+    for message in input {
+        match message {
+            ReadManifestIn::SchemaVersion { version } => check_message_format_version(version)?,
+            ReadManifestIn::LockedPackage { package } => locked_packages.push(package),
+            ReadManifestIn::UnusedPatches { unused } => unused_patches = Some(unused),
+        }
+    }
+
+    if locked_packages.is_empty() {
+        anyhow::bail!("incomplete input. no packages found.");
+    }
+
+    let resolve = into_resolve(ws, locked_packages, unused_patches.unwrap_or_default())?;
+    let mut ids: Vec<PackageId> = resolve
+        .iter()
+        .filter(|id| !ws.members().any(|member| member.package_id() == *id))
+        .collect();
+    ids.sort();
+
+    let registry = ws.package_registry()?;
+    let pkg_set = get_resolved_packages(&resolve, registry)?;
+
+    let mut out = Vec::new();
+    for pkg in pkg_set.get_many(ids)? {
+        out.extend(manifest_messages(
+            opts,
+            false,
+            &gctx.cwd().join(pkg.manifest_path()),
+            ManifestRef::Real(pkg.manifest()),
+        )?);
+    }
+
+    Ok(out)
+}
+
+/// Describes the members of `ws` along with the `members` pattern or the path dependency that
+/// made each of them one.
+fn workspace_message(gctx: &GlobalContext, ws: &Workspace<'_>) -> CargoResult<ReadManifestOut> {
+    let toml_workspace = match ws.root_maybe() {
+        MaybePackage::Package(pkg) => pkg.manifest().normalized_toml().workspace.as_ref(),
+        MaybePackage::Virtual(v) => v.normalized_toml().workspace.as_ref(),
+    };
+    let patterns = toml_workspace
+        .and_then(|w| w.members.as_ref())
+        .into_iter()
+        .flatten()
+        .map(|pattern| {
+            // Member roots are normalized, so patterns such as `../sibling` have to be as well.
+            let path = paths::normalize_path(&ws.root().join(pattern));
+            let glob = glob::Pattern::new(&path.to_string_lossy())
+                .with_context(|| format!("invalid `members` pattern `{pattern}`"))?;
+            Ok((pattern, glob))
+        })
+        .collect::<CargoResult<Vec<_>>>()?;
+
+    let mut members = Vec::new();
+    for member in ws.members() {
+        let root = member.manifest_path() == ws.root_manifest();
+        let pattern = patterns
+            .iter()
+            .find(|(_, glob)| glob.matches_path(member.root()))
+            .map(|(pattern, _)| (*pattern).clone());
+        // Cargo also makes the path dependencies of members into members.
+        let path_dependency_of = if root || pattern.is_some() {
+            None
+        } else {
+            ws.members()
+                .find(|dependent| {
+                    dependent
+                        .dependencies()
+                        .iter()
+                        .any(|dep| dep.source_id() == member.package_id().source_id())
+                })
+                .map(|dependent| dependent.package_id().to_spec())
+        };
+
+        members.push(WorkspaceMember {
+            id: member.package_id().to_spec(),
+            path: Utf8PathBuf::try_from(gctx.cwd().join(member.manifest_path()))?,
+            root,
+            pattern,
+            path_dependency_of,
+        });
+    }
+
+    Ok(ReadManifestOut::Workspace {
+        path: Utf8PathBuf::try_from(gctx.cwd().join(ws.root_manifest()))?,
+        members,
+        default_members: ws
+            .default_members()
+            .map(|pkg| pkg.package_id().to_spec())
+            .collect(),
+        exclude: toml_workspace
+            .and_then(|w| w.exclude.clone())
+            .unwrap_or_default(),
+        resolver: ws.resolve_behavior().to_manifest(),
+    })
+}
+
+/// A package manifest or a virtual manifest
+#[derive(Clone, Copy)]
+enum ManifestRef<'a> {
+    Real(&'a Manifest),
+    Virtual(&'a VirtualManifest),
+}
+
+impl<'a> From<&'a EitherManifest> for ManifestRef<'a> {
+    fn from(manifest: &'a EitherManifest) -> Self {
+        match manifest {
+            EitherManifest::Real(r) => ManifestRef::Real(r),
+            EitherManifest::Virtual(v) => ManifestRef::Virtual(v),
+        }
+    }
+}
+
+impl<'a> ManifestRef<'a> {
+    fn pkg_id(self) -> Option<PackageIdSpec> {
+        match self {
+            ManifestRef::Real(r) => Some(r.package_id().to_spec()),
+            ManifestRef::Virtual(_) => None,
+        }
+    }
+
+    fn normalized_toml(self) -> &'a TomlManifest {
+        match self {
+            ManifestRef::Real(r) => r.normalized_toml(),
+            ManifestRef::Virtual(v) => v.normalized_toml(),
+        }
+    }
+
+    fn original_toml(self) -> &'a TomlManifest {
+        match self {
+            ManifestRef::Real(r) => r.original_toml(),
+            ManifestRef::Virtual(v) => v.original_toml(),
+        }
+    }
+
+    fn warnings(self) -> &'a Warnings {
+        match self {
+            ManifestRef::Real(r) => r.warnings(),
+            ManifestRef::Virtual(v) => v.warnings(),
+        }
+    }
+
+    fn is_embedded(self) -> bool {
+        match self {
+            ManifestRef::Real(r) => r.is_embedded(),
+            ManifestRef::Virtual(_) => false,
+        }
+    }
+
+    fn targets(self) -> Option<&'a [Target]> {
+        match self {
+            ManifestRef::Real(r) => Some(r.targets()),
+            ManifestRef::Virtual(_) => None,
+        }
+    }
+}
+
+/// Builds the [`ReadManifestOut::Manifest`] message for the manifest at `path`, followed by its
+/// warnings when [`ReadManifestOptions::report_warnings`] is set.
+fn manifest_messages(
+    opts: &ReadManifestOptions,
+    workspace: bool,
+    path: &Path,
+    manifest: ManifestRef<'_>,
+) -> CargoResult<Vec<ReadManifestOut>> {
+    let contents = if opts.original || opts.spans || opts.report_warnings {
+        Some(paths::read(path)?)
+    } else {
+        None
+    };
+    if opts.spans && manifest.is_embedded() {
+        anyhow::bail!(
+            "`--spans` isn't supported for the embedded manifest at `{}`",
+            path.display()
+        );
+    }
+    // The manifest of a script is in its frontmatter, so there is no TOML document to parse.
+    let table = match &contents {
+        Some(contents) if (opts.spans || opts.report_warnings) && !manifest.is_embedded() => Some(
+            DeTable::parse(contents)
+                .with_context(|| format!("failed to parse manifest at `{}`", path.display()))?,
+        ),
+        _ => None,
+    };
+    let spans = match (&contents, &table) {
+        (Some(contents), Some(table)) if opts.spans => Some(manifest_spans(contents, table)),
+        _ => None,
+    };
+    let unused = match &table {
+        Some(table) if opts.report_warnings => unused_keys(table)
+            .with_context(|| format!("failed to parse manifest at `{}`", path.display()))?,
+        _ => BTreeSet::new(),
+    };
+    let (original, document) = if opts.original {
+        (Some(manifest.original_toml().clone()), contents)
+    } else {
+        (None, None)
+    };
+
+    let targets = match manifest.targets() {
+        Some(targets) if opts.targets => Some(targets.iter().map(manifest_target).collect()),
+        _ => None,
+    };
+
+    let path = Utf8PathBuf::try_from(path.to_owned())?;
+    let mut out = vec![ReadManifestOut::Manifest {
+        workspace,
+        path: path.clone(),
+        embedded: manifest.is_embedded(),
+        pkg_id: manifest.pkg_id(),
+        manifest: manifest.normalized_toml().clone(),
+        original,
+        document,
+        spans,
+        targets,
+    }];
+
+    if opts.report_warnings {
+        for warning in manifest.warnings().warnings() {
+            // Cargo words its unused key warnings as `unused manifest key: <key>`, optionally
+            // followed by a hint on the next lines.
+            let key = warning
+                .message
+                .strip_prefix("unused manifest key: ")
+                .and_then(|rest| {
+                    unused.iter().find(|key| {
+                        rest.strip_prefix(key.as_str())
+                            .is_some_and(|hint| hint.is_empty() || hint.starts_with('\n'))
+                    })
+                })
+                .cloned();
+            out.push(ReadManifestOut::Warning {
+                path: path.clone(),
+                message: warning.message.clone(),
+                key,
+                critical: warning.is_critical,
+            });
+        }
+    }
+
+    Ok(out)
+}
+
+/// Describes `target` the way `cargo metadata` does.
+fn manifest_target(target: &Target) -> ManifestTarget {
+    ManifestTarget {
+        kind: target_kind_names(target.kind()),
+        crate_types: target
+            .rustc_crate_types()
+            .iter()
+            .map(|ct| ct.to_string())
+            .collect(),
+        name: target.name().to_owned(),
+        src_path: target.src_path().path().map(|path| path.to_owned()),
+        edition: target.edition().to_string(),
+        required_features: target.required_features().cloned(),
+        doc: target.documented(),
+        doctest: target.doctested() && target.doctestable(),
+        test: target.tested(),
+        harness: target.harness(),
+    }
+}
+
+/// Maps the path of each table, key and array element in `contents`, such as
+/// `dependencies.serde.version` or `bin[0].name`, to where it is written.
+fn manifest_spans(contents: &str, table: &Spanned<DeTable<'_>>) -> BTreeMap<String, ManifestSpan> {
+    let mut spans = BTreeMap::new();
+    collect_table_spans(contents, "", table.get_ref(), &mut spans);
+    spans
+}
+
+fn collect_table_spans(
+    contents: &str,
+    prefix: &str,
+    table: &DeTable<'_>,
+    spans: &mut BTreeMap<String, ManifestSpan>,
+) {
+    for (key, value) in table {
+        let path = join_key(prefix, key.get_ref());
+        spans.insert(
+            path.clone(),
+            ManifestSpan {
+                key: Some(span(contents, key.span())),
+                value: span(contents, value.span()),
+            },
+        );
+        collect_value_spans(contents, &path, value.get_ref(), spans);
+    }
+}
+
+fn collect_value_spans(
+    contents: &str,
+    path: &str,
+    value: &DeValue<'_>,
+    spans: &mut BTreeMap<String, ManifestSpan>,
+) {
+    match value {
+        DeValue::Table(table) => collect_table_spans(contents, path, table, spans),
+        DeValue::Array(array) => {
+            for (i, item) in array.iter().enumerate() {
+                let path = format!("{path}[{i}]");
+                spans.insert(
+                    path.clone(),
+                    ManifestSpan {
+                        key: None,
+                        value: span(contents, item.span()),
+                    },
+                );
+                collect_value_spans(contents, &path, item.get_ref(), spans);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Collects the keys of `table` that aren't part of a manifest, the way Cargo does when warning
+/// about them.
+fn unused_keys(table: &Spanned<DeTable<'_>>) -> CargoResult<BTreeSet<String>> {
+    let mut unused = BTreeSet::new();
+    let deserializer = toml::de::Deserializer::from(table.clone());
+    let _: TomlManifest = serde_ignored::deserialize(deserializer, |path| {
+        let mut key = String::new();
+        stringify(&mut key, &path);
+        unused.insert(key);
+    })?;
+    Ok(unused)
+}
+
+/// Writes `path` as the dotted key Cargo uses in its unused key warnings.
+fn stringify(dst: &mut String, path: &serde_ignored::Path<'_>) {
+    use serde_ignored::Path;
+
+    match *path {
+        Path::Root => {}
+        Path::Seq { parent, index } => {
+            stringify(dst, parent);
+            if !dst.is_empty() {
+                dst.push('.');
+            }
+            dst.push_str(&index.to_string());
+        }
+        Path::Map { parent, ref key } => {
+            stringify(dst, parent);
+            if !dst.is_empty() {
+                dst.push('.');
+            }
+            dst.push_str(key);
+        }
+        Path::Some { parent }
+        | Path::NewtypeVariant { parent }
+        | Path::NewtypeStruct { parent } => stringify(dst, parent),
+    }
+}
+
+/// Appends `key` to the dotted `prefix`, quoting it unless it is a bare key.
+fn join_key(prefix: &str, key: &str) -> String {
+    let bare = !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let key = if bare { key.to_owned() } else { quote_key(key) };
+
+    if prefix.is_empty() {
+        key
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+/// Quotes `key` as a TOML basic string.
+fn quote_key(key: &str) -> String {
+    let mut quoted = String::with_capacity(key.len() + 2);
+    quoted.push('"');
+    for c in key.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\u{8}' => quoted.push_str("\\b"),
+            '\t' => quoted.push_str("\\t"),
+            '\n' => quoted.push_str("\\n"),
+            '\u{c}' => quoted.push_str("\\f"),
+            '\r' => quoted.push_str("\\r"),
+            c if c <= '\u{1f}' || c == '\u{7f}' => {
+                quoted.push_str(&format!("\\u{:04X}", c as u32));
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Locates the byte `range` of `contents` by line and column, both starting from 1.
+fn span(contents: &str, range: Range<usize>) -> Span {
+    let before = &contents[..range.start];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    Span {
+        start: range.start,
+        end: range.end,
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
@@ -1,16 +1,22 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::PathBuf;
+use std::task::Poll;
 
 use anyhow::{anyhow, Context as _};
+use cargo::core::registry::{PackageRegistry, Registry};
 use cargo::core::{
-    PackageId, PackageIdSpec, Resolve, ResolveVersion, SourceId, SourceKind, Workspace,
+    Dependency, PackageId, PackageIdSpec, Resolve, ResolveVersion, SourceId, SourceKind, Workspace,
 };
+use cargo::sources::source::QueryKind;
+use cargo::sources::{IndexSummary, SourceConfigMap};
 use cargo::util::Graph;
-use cargo::CargoResult;
+use cargo::{CargoResult, GlobalContext};
 use cargo_plumbing_schemas::lockfile::{NormalizedDependency, NormalizedPatch, NormalizedResolve};
 use cargo_util_schemas::lockfile::{
     TomlLockfile, TomlLockfileDependency, TomlLockfileMetadata, TomlLockfilePackageId,
     TomlLockfilePatch,
 };
+use url::Url;
 
 use crate::cargo::core::resolver::encode::build_path_deps;
 
@@ -206,13 +212,14 @@ pub fn spec_to_id(
                             .with_git_precise(git_rev);
                         Ok(source_id)
                     }
+                    // Alternative registries are identified by their index URL, so they don't need
+                    // their name from the config to be reconstructed.
                     SourceKind::Registry | SourceKind::SparseRegistry => {
                         SourceId::for_registry(url)
                     }
-                    SourceKind::Path => SourceId::for_path(
-                        &url.to_file_path().map_err(|_| anyhow!("invalid path"))?,
-                    ),
-                    _ => anyhow::bail!("unsupported source"),
+                    SourceKind::LocalRegistry => SourceId::for_local_registry(&url_to_path(url)?),
+                    SourceKind::Directory => SourceId::for_directory(&url_to_path(url)?),
+                    SourceKind::Path => SourceId::for_path(&url_to_path(url)?),
                 }?;
 
                 return Ok(Some(PackageId::new(name.into(), version, source_id)));
@@ -230,6 +237,64 @@ pub fn spec_to_id(
     Ok(None)
 }
 
+fn url_to_path(url: &Url) -> CargoResult<PathBuf> {
+    url.to_file_path()
+        .map_err(|_| anyhow!("invalid path `{url}` in package ID specification"))
+}
+
+/// Queries `registry` for the summaries matching `dep`, blocking until they are available.
+pub fn query(
+    registry: &mut PackageRegistry<'_>,
+    dep: &Dependency,
+) -> CargoResult<Vec<IndexSummary>> {
+    loop {
+        match registry.query_vec(dep, QueryKind::Exact) {
+            Poll::Ready(summaries) => return summaries,
+            Poll::Pending => registry.block_until_ready()?,
+        }
+    }
+}
+
+/// Like [`query`], but also returns the summaries a resolve rejects, such as yanked versions or
+/// versions that aren't downloaded when offline.
+pub fn query_rejected(
+    registry: &mut PackageRegistry<'_>,
+    dep: &Dependency,
+) -> CargoResult<Vec<IndexSummary>> {
+    loop {
+        match registry.query_vec(dep, QueryKind::RejectedVersions) {
+            Poll::Ready(summaries) => return summaries,
+            Poll::Pending => registry.block_until_ready()?,
+        }
+    }
+}
+
+/// Looks up the sources that `source_ids` are replaced with by `[source]` tables in the Cargo
+/// configuration, leaving out the ones that aren't replaced.
+pub fn source_replacements(
+    gctx: &GlobalContext,
+    source_ids: impl IntoIterator<Item = SourceId>,
+) -> CargoResult<Vec<(SourceId, SourceId)>> {
+    let source_ids = source_ids
+        .into_iter()
+        .filter(|source_id| !source_id.is_path())
+        .map(|source_id| source_id.without_precise())
+        .collect::<BTreeSet<_>>();
+
+    let source_config = SourceConfigMap::new(gctx)?;
+    let mut replacements = Vec::new();
+    for source_id in source_ids {
+        let replaced_with = source_config
+            .load(source_id, &HashSet::new())?
+            .replaced_source_id();
+        if replaced_with != source_id {
+            replacements.push((source_id, replaced_with));
+        }
+    }
+
+    Ok(replacements)
+}
+
 /// Normalizes [`TomlLockfile`] into [`NormalizedResolve`].
 ///
 /// This is used when outputting a message containing a resolve result, i.e. for `read-lockfile`
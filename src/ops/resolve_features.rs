@@ -0,0 +1,286 @@
+use cargo::core::compiler::{CompileKind, RustcTargetData};
+use cargo::core::resolver::features::{FeatureOpts, FeatureResolver};
+use cargo::core::resolver::{CliFeatures, ForceAllTargets, HasDevUnits};
+use cargo::core::Workspace;
+use cargo::ops::{get_resolved_packages, resolve_with_previous};
+use cargo::CargoResult;
+use cargo_plumbing_schemas::resolve_features::{ResolveFeaturesIn, ResolveFeaturesOut};
+
+use crate::ops::check_message_format_version;
+use crate::ops::resolve::into_resolve;
+
+/// Options for [`resolve_features`]
+#[derive(Debug, Default)]
+pub struct ResolveFeaturesOptions {
+    /// List of features to activate
+    pub features: Vec<String>,
+    /// Activate all available features
+    pub all_features: bool,
+    /// Do not activate the `default` feature
+    pub no_default_features: bool,
+    /// Target triples
+    pub target: Vec<String>,
+    /// Include this package's library
+    pub lib: bool,
+    /// Include all binaries
+    pub bins: bool,
+    /// Include only the specified binaries
+    pub bin: Vec<String>,
+    /// Include all examples
+    pub examples: bool,
+    /// Include only the specified examples
+    pub example: Vec<String>,
+    /// Include all tests
+    pub tests: bool,
+    /// Include only the specified tests
+    pub test: Vec<String>,
+    /// Include all benches
+    pub benches: bool,
+    /// Include only the specified benches
+    pub bench: Vec<String>,
+    /// Include all targets
+    pub all_targets: bool,
+}
+
+/// Resolves the features of `ws` from the manifests and locked packages in `input`.
+pub fn resolve_features(
+    ws: &Workspace<'_>,
+    opts: &ResolveFeaturesOptions,
+    input: impl IntoIterator<Item = ResolveFeaturesIn>,
+) -> CargoResult<Vec<ResolveFeaturesOut>> {
+    let gctx = ws.gctx();
+
+    let mut ws_manifest = None;
+    let mut locked_packages = Vec::new();
+    let mut unused_patches = None;
+    let mut specs = Vec::new();
+
+    for message in input {
+        match message {
+            ResolveFeaturesIn::SchemaVersion { version } => check_message_format_version(version)?,
+            ResolveFeaturesIn::LockedPackage { package } => locked_packages.push(package),
+            ResolveFeaturesIn::UnusedPatches { unused } => unused_patches = Some(unused),
+            ResolveFeaturesIn::Manifest {
+                pkg_id,
+                manifest,
+                workspace,
+                ..
+            } => {
+                if workspace {
+                    if ws_manifest.is_some() {
+                        anyhow::bail!("duplicate workspace manifest input.");
+                    }
+                    ws_manifest = Some(manifest);
+                }
+                if let Some(id) = pkg_id {
+                    specs.push(id);
+                }
+            }
+        }
+    }
+
+    if locked_packages.is_empty() {
+        anyhow::bail!("incomplete input. no packages found.");
+    }
+    let Some(ws_manifest) = ws_manifest else {
+        anyhow::bail!("missing workspace manifest input.")
+    };
+
+    let mut out = Vec::new();
+
+    let mut requested_features = opts.features.clone();
+    if let Some(bins) = ws_manifest.bin {
+        let bins = bins
+            .into_iter()
+            .filter(|bin| {
+                opts.all_targets
+                    || opts.bins
+                    || bin
+                        .name
+                        .as_ref()
+                        .map(|name| opts.bin.contains(name))
+                        .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+        for bin in bins.iter() {
+            if let Some(name) = &bin.name {
+                out.push(ResolveFeaturesOut::Target {
+                    name: name.clone(),
+                    kind: "bin".to_owned(),
+                });
+            }
+        }
+        let bins = bins
+            .into_iter()
+            .filter_map(|bin| bin.required_features)
+            .flatten()
+            .collect::<Vec<_>>();
+        requested_features.extend(bins);
+    }
+    if let Some(tests) = ws_manifest.test {
+        let tests = tests
+            .into_iter()
+            .filter(|test| {
+                opts.all_targets
+                    || opts.tests
+                    || test
+                        .name
+                        .as_ref()
+                        .map(|name| opts.test.contains(name))
+                        .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+        for test in tests.iter() {
+            if let Some(name) = &test.name {
+                out.push(ResolveFeaturesOut::Target {
+                    name: name.clone(),
+                    kind: "test".to_owned(),
+                });
+            }
+        }
+        let tests = tests
+            .into_iter()
+            .filter_map(|test| test.required_features)
+            .flatten()
+            .collect::<Vec<_>>();
+        requested_features.extend(tests);
+    }
+    if let Some(benches) = ws_manifest.bench {
+        let benches = benches
+            .into_iter()
+            .filter(|bench| {
+                opts.all_targets
+                    || opts.benches
+                    || bench
+                        .name
+                        .as_ref()
+                        .map(|name| opts.bench.contains(name))
+                        .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+        for bench in benches.iter() {
+            if let Some(name) = &bench.name {
+                out.push(ResolveFeaturesOut::Target {
+                    name: name.clone(),
+                    kind: "bench".to_owned(),
+                });
+            }
+        }
+        let benches = benches
+            .into_iter()
+            .filter_map(|bench| bench.required_features)
+            .flatten()
+            .collect::<Vec<_>>();
+        requested_features.extend(benches);
+    }
+    if let Some(examples) = ws_manifest.example {
+        let examples = examples
+            .into_iter()
+            .filter(|example| {
+                opts.all_targets
+                    || opts.examples
+                    || example
+                        .name
+                        .as_ref()
+                        .map(|name| opts.example.contains(name))
+                        .unwrap_or(false)
+            })
+            .collect::<Vec<_>>();
+        for example in examples.iter() {
+            if let Some(name) = &example.name {
+                out.push(ResolveFeaturesOut::Target {
+                    name: name.clone(),
+                    kind: "example".to_owned(),
+                });
+            }
+        }
+        let examples = examples
+            .into_iter()
+            .filter_map(|example| example.required_features)
+            .flatten()
+            .collect::<Vec<_>>();
+        requested_features.extend(examples);
+    }
+
+    let resolve = into_resolve(ws, locked_packages, unused_patches.unwrap_or_default())?;
+    let cli_features = CliFeatures::from_command_line(
+        &requested_features,
+        opts.all_features,
+        !opts.no_default_features,
+    )?;
+
+    // Determine if we should include dev units from the selected targets.
+    let has_dev_units = if opts.examples
+        || opts.tests
+        || opts.benches
+        || !opts.example.is_empty()
+        || !opts.test.is_empty()
+        || !opts.bench.is_empty()
+        || opts.all_targets
+    {
+        HasDevUnits::Yes
+    } else {
+        HasDevUnits::No
+    };
+
+    let mut registry = ws.package_registry()?;
+    let add_patches = true;
+    let resolve_with_overrides = resolve_with_previous(
+        &mut registry,
+        ws,
+        &cli_features,
+        has_dev_units,
+        Some(&resolve),
+        None,
+        &specs,
+        add_patches,
+    )?;
+
+    let requested_kinds = CompileKind::from_requested_targets(gctx, &opts.target)?;
+    let mut target_data = RustcTargetData::new(ws, &requested_kinds)?;
+    let members_with_features = ws.members_with_features(&specs, &cli_features)?;
+    let member_ids = members_with_features
+        .iter()
+        .map(|(p, _fts)| p.package_id())
+        .collect::<Vec<_>>();
+    let force_all_targets = ForceAllTargets::No;
+
+    // HACK: The resolver must download packages before it can resolve features. This is a
+    // workaround for a known limitation of the feature resolver.
+    //
+    // See: https://github.com/rust-lang/cargo/issues/15834
+    let pkg_set = get_resolved_packages(&resolve_with_overrides, registry)?;
+    pkg_set.download_accessible(
+        &resolve_with_overrides,
+        &member_ids,
+        has_dev_units,
+        &requested_kinds,
+        &target_data,
+        force_all_targets,
+    )?;
+
+    let feature_opts = FeatureOpts::new(ws, has_dev_units, force_all_targets)?;
+    let resolved_features = FeatureResolver::resolve(
+        ws,
+        &mut target_data,
+        &resolve_with_overrides,
+        &pkg_set,
+        &cli_features,
+        &specs,
+        &requested_kinds,
+        feature_opts,
+    )?;
+
+    for ((id, feat_for), feats) in resolved_features.activated_features {
+        let id = id.to_spec();
+        let features_for = feat_for.to_string();
+        let features = feats.iter().map(|feat| feat.to_string()).collect();
+        out.push(ResolveFeaturesOut::Activated {
+            id,
+            features_for,
+            features,
+        });
+    }
+
+    Ok(out)
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use cargo::CargoResult;
+use cargo_plumbing_schemas::lock_dependencies::LockDependenciesIn;
+use cargo_plumbing_schemas::lockfile::NormalizedDependency;
+use cargo_plumbing_schemas::plan_build::PlanBuildIn;
+use cargo_plumbing_schemas::read_manifest::ReadManifestIn;
+use cargo_plumbing_schemas::resolve_features::ResolveFeaturesIn;
+use cargo_plumbing_schemas::validate::ValidateOut;
+use cargo_plumbing_schemas::vendor::VendorIn;
+use cargo_plumbing_schemas::write_lockfile::WriteLockfileIn;
+use cargo_util_schemas::core::PackageIdSpec;
+
+use crate::ops::plan_build::TARGET_KINDS;
+use crate::ops::{check_message_format_version, Command};
+
+/// Checks `input` as the jsonlines input of `command`, returning a diagnostic for each problem.
+///
+/// Besides checking each line against the input messages of `command`, this looks for problems
+/// that would otherwise surface as hard to trace errors while resolving:
+///
+/// - Packages locked more than once
+/// - Dependencies not matching any locked package
+/// - Missing or duplicate workspace manifest, for `resolve-features`
+/// - Unknown target kinds, for `plan-build`
+pub fn validate(command: Command, input: impl BufRead) -> CargoResult<Vec<ValidateOut>> {
+    if matches!(
+        command,
+        Command::LocateManifest | Command::ReadLockfile | Command::QueryIndex | Command::Validate
+    ) {
+        anyhow::bail!("`{command}` doesn't read input messages");
+    }
+
+    let mut diagnostics = Vec::new();
+
+    let mut packages: Vec<(usize, NormalizedDependency)> = Vec::new();
+    let mut seen_packages: HashMap<PackageIdSpec, usize> = HashMap::new();
+    let mut ws_manifest_line = None;
+
+    for (i, line) in input.lines().enumerate() {
+        let line_no = i + 1;
+        let line = line?;
+
+        let message = match parse(command, &line) {
+            Ok(message) => message,
+            Err(err) => {
+                diagnostics.push((
+                    Some(line_no),
+                    format!("invalid `{command}` input message: {err}"),
+                ));
+                continue;
+            }
+        };
+
+        match message {
+            Message::SchemaVersion(version) => {
+                if let Err(err) = check_message_format_version(version) {
+                    diagnostics.push((Some(line_no), err.to_string()));
+                }
+            }
+            Message::LockedPackage(package) => {
+                if let Some(first) = seen_packages.get(&package.id) {
+                    diagnostics.push((
+                        Some(line_no),
+                        format!(
+                            "package `{}` is specified twice, first on line {first}",
+                            package.id
+                        ),
+                    ));
+                } else {
+                    seen_packages.insert(package.id.clone(), line_no);
+                }
+                packages.push((line_no, package));
+            }
+            Message::WorkspaceManifest => {
+                if let Some(first) = ws_manifest_line {
+                    diagnostics.push((
+                        Some(line_no),
+                        format!("duplicate workspace manifest, first on line {first}"),
+                    ));
+                } else {
+                    ws_manifest_line = Some(line_no);
+                }
+            }
+            Message::Target(kind) => {
+                if !TARGET_KINDS.contains(&kind.as_str()) {
+                    diagnostics.push((
+                        Some(line_no),
+                        format!(
+                            "unknown target kind `{kind}`, expected one of `{}`",
+                            TARGET_KINDS.join("`, `")
+                        ),
+                    ));
+                }
+            }
+            Message::Other => {}
+        }
+    }
+
+    for (line_no, package) in &packages {
+        for dep in package.dependencies.iter().flatten() {
+            let found = packages.iter().any(|(_, p)| matches_spec(dep, &p.id));
+            if !found {
+                diagnostics.push((
+                    Some(*line_no),
+                    format!(
+                        "dependency `{dep}` of `{}` doesn't match any locked package",
+                        package.id
+                    ),
+                ));
+            }
+        }
+    }
+
+    if command == Command::ResolveFeatures && ws_manifest_line.is_none() {
+        diagnostics.push((None, "missing workspace manifest".to_owned()));
+    }
+
+    diagnostics.sort_by_key(|(line, _)| line.unwrap_or(usize::MAX));
+    let out = diagnostics
+        .into_iter()
+        .map(|(line, message)| ValidateOut::Diagnostic { line, message })
+        .collect();
+
+    Ok(out)
+}
+
+/// The parts of an input message relevant to validation
+enum Message {
+    SchemaVersion(u32),
+    LockedPackage(NormalizedDependency),
+    WorkspaceManifest,
+    Target(String),
+    Other,
+}
+
+fn parse(command: Command, line: &str) -> serde_json::Result<Message> {
+    let message = match command {
+        Command::ReadManifest => match serde_json::from_str(line)? {
+            ReadManifestIn::SchemaVersion { version } => Message::SchemaVersion(version),
+            ReadManifestIn::LockedPackage { package } => Message::LockedPackage(package),
+            ReadManifestIn::UnusedPatches { .. } => Message::Other,
+        },
+        Command::LockDependencies => match serde_json::from_str(line)? {
+            LockDependenciesIn::SchemaVersion { version } => Message::SchemaVersion(version),
+            LockDependenciesIn::LockedPackage { package } => Message::LockedPackage(package),
+            LockDependenciesIn::UnusedPatches { .. } => Message::Other,
+        },
+        Command::WriteLockfile => match serde_json::from_str(line)? {
+            WriteLockfileIn::SchemaVersion { version } => Message::SchemaVersion(version),
+            WriteLockfileIn::LockedPackage { package } => Message::LockedPackage(package),
+            WriteLockfileIn::Lockfile { .. } | WriteLockfileIn::UnusedPatches { .. } => {
+                Message::Other
+            }
+        },
+        Command::ResolveFeatures => match serde_json::from_str(line)? {
+            ResolveFeaturesIn::SchemaVersion { version } => Message::SchemaVersion(version),
+            ResolveFeaturesIn::LockedPackage { package } => Message::LockedPackage(package),
+            ResolveFeaturesIn::Manifest {
+                workspace: true, ..
+            } => Message::WorkspaceManifest,
+            ResolveFeaturesIn::Manifest { .. } | ResolveFeaturesIn::UnusedPatches { .. } => {
+                Message::Other
+            }
+        },
+        Command::PlanBuild => match serde_json::from_str(line)? {
+            PlanBuildIn::SchemaVersion { version } => Message::SchemaVersion(version),
+            PlanBuildIn::LockedPackage { package } => Message::LockedPackage(package),
+            PlanBuildIn::Target { kind, .. } => Message::Target(kind),
+            PlanBuildIn::Manifest { .. }
+            | PlanBuildIn::UnusedPatches { .. }
+            | PlanBuildIn::Activated { .. } => Message::Other,
+        },
+        Command::Vendor => match serde_json::from_str(line)? {
+            VendorIn::SchemaVersion { version } => Message::SchemaVersion(version),
+            VendorIn::LockedPackage { package } => Message::LockedPackage(package),
+            VendorIn::UnusedPatches { .. } => Message::Other,
+        },
+        Command::LocateManifest
+        | Command::ReadLockfile
+        | Command::QueryIndex
+        | Command::Validate => {
+            unreachable!("`{command}` doesn't read input messages")
+        }
+    };
+    Ok(message)
+}
+
+/// Whether `dep`, a dependency edge of a locked package, could refer to the package `id`.
+///
+/// Dependency edges may leave out the version and source when they are unambiguous.
+fn matches_spec(dep: &PackageIdSpec, id: &PackageIdSpec) -> bool {
+    dep.name() == id.name()
+        && dep
+            .version()
+            .is_none_or(|version| id.version() == Some(version))
+        && dep.url().is_none_or(|url| id.url() == Some(url))
+}
@@ -0,0 +1,212 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context as _;
+use cargo::core::{GitReference, Package, PackageId, SourceId, Workspace};
+use cargo::ops::get_resolved_packages;
+use cargo::sources::PathSource;
+use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing_schemas::vendor::{VendorIn, VendorOut};
+use cargo_util::Sha256;
+
+use crate::ops::check_message_format_version;
+use crate::ops::resolve::into_resolve;
+
+/// The name of the `[source]` table for the vendor directory.
+const VENDORED_SOURCES: &str = "vendored-sources";
+
+/// Files left in package sources by Cargo that aren't part of the package.
+const SKIPPED_FILES: &[&str] = &[".git", ".cargo-ok"];
+
+/// Copies the sources of the locked packages in `input` into `vendor_path`.
+///
+/// The files of each package are copied to a `<name>-<version>` directory along with a
+/// `.cargo-checksum.json`, making `vendor_path` usable as a `directory` source. Path dependencies
+/// aren't vendored, and like `cargo vendor`, the same version of a package from two sources is an
+/// error and everything else in `vendor_path`, except for hidden files, is removed.
+pub fn vendor(
+    ws: &Workspace<'_>,
+    vendor_path: &Path,
+    input: impl IntoIterator<Item = VendorIn>,
+) -> CargoResult<Vec<VendorOut>> {
+    let gctx = ws.gctx();
+
+    let mut locked_packages = Vec::new();
+    let mut unused_patches = None;
+
+    for message in input {
+        match message {
+            VendorIn::SchemaVersion { version } => check_message_format_version(version)?,
+            VendorIn::LockedPackage { package } => locked_packages.push(package),
+            VendorIn::UnusedPatches { unused } => unused_patches = Some(unused),
+        }
+    }
+
+    if locked_packages.is_empty() {
+        anyhow::bail!("incomplete input. no packages found.");
+    }
+
+    let resolve = into_resolve(ws, locked_packages, unused_patches.unwrap_or_default())?;
+    let mut ids: Vec<PackageId> = resolve
+        .iter()
+        .filter(|id| !id.source_id().is_path())
+        .collect();
+    ids.sort();
+
+    let mut vendored = HashMap::new();
+    for id in &ids {
+        if let Some(prev) = vendored.insert((id.name(), id.version()), id.source_id()) {
+            anyhow::bail!(
+                "found duplicate version of package `{} v{}` vendored from two sources:\n\
+                 \n\
+                 \tsource 1: {}\n\
+                 \tsource 2: {}",
+                id.name(),
+                id.version(),
+                prev,
+                id.source_id()
+            );
+        }
+    }
+
+    let registry = ws.package_registry()?;
+    let pkg_set = get_resolved_packages(&resolve, registry)?;
+    let packages = pkg_set.get_many(ids.iter().copied())?;
+
+    let vendor_path = gctx.cwd().join(vendor_path);
+
+    // A `directory` source serves every package in it, so packages left over from vendoring
+    // another lockfile have to go.
+    let mut to_remove = HashSet::new();
+    if vendor_path.is_dir() {
+        for entry in fs::read_dir(&vendor_path)? {
+            let entry = entry?;
+            if !entry.file_name().to_string_lossy().starts_with('.') {
+                to_remove.insert(entry.path());
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    let mut sources = BTreeSet::new();
+    for pkg in packages {
+        let id = pkg.package_id();
+        let dst = vendor_path.join(format!("{}-{}", id.name(), id.version()));
+        to_remove.remove(&dst);
+        if dst.exists() {
+            fs::remove_dir_all(&dst)
+                .with_context(|| format!("failed to remove `{}`", dst.display()))?;
+        }
+
+        let files =
+            copy_files(gctx, pkg, &dst).with_context(|| format!("failed to vendor `{id}`"))?;
+        let checksum = resolve.checksums().get(&id).cloned().flatten();
+        let checksum_json = serde_json::json!({ "files": files, "package": checksum });
+        fs::write(
+            dst.join(".cargo-checksum.json"),
+            serde_json::to_string(&checksum_json)?,
+        )?;
+
+        sources.insert(id.source_id().without_precise());
+        out.push(VendorOut::VendoredPackage {
+            id: id.to_spec(),
+            path: dst,
+        });
+    }
+
+    for path in to_remove {
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        removed.with_context(|| format!("failed to remove `{}`", path.display()))?;
+    }
+
+    for source_id in sources {
+        out.push(source_replacement(source_id));
+    }
+    out.push(VendorOut::Source {
+        name: VENDORED_SOURCES.to_owned(),
+        config: BTreeMap::from([("directory".to_owned(), vendor_path.display().to_string())]),
+    });
+
+    Ok(out)
+}
+
+/// Copies the files of `pkg` into `dst`, returning their checksums by their path relative to the
+/// package root.
+///
+/// Only the files its source lists for the package are copied, which honors the `include` and
+/// `exclude` fields of its manifest.
+fn copy_files(
+    gctx: &GlobalContext,
+    pkg: &Package,
+    dst: &Path,
+) -> CargoResult<BTreeMap<String, String>> {
+    let source = PathSource::new(pkg.root(), pkg.package_id().source_id(), gctx);
+
+    fs::create_dir_all(dst)?;
+    let mut files = BTreeMap::new();
+    for path in source.list_files(pkg)? {
+        let path = path.into_path_buf();
+        let relative = path.strip_prefix(pkg.root())?;
+        if relative.components().any(|c| {
+            SKIPPED_FILES
+                .iter()
+                .any(|skipped| c.as_os_str() == *skipped)
+        }) {
+            continue;
+        }
+
+        let file_dst = dst.join(relative);
+        if let Some(parent) = file_dst.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&path, &file_dst)?;
+        let cksum = Sha256::new().update_path(&path)?.finish_hex();
+        files.insert(relative_to_string(relative), cksum);
+    }
+    Ok(files)
+}
+
+/// Paths in `.cargo-checksum.json` always use `/` as the separator.
+fn relative_to_string(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The `[source]` table replacing `source_id` with the vendor directory, like `cargo vendor`
+/// prints.
+fn source_replacement(source_id: SourceId) -> VendorOut {
+    let mut config = BTreeMap::new();
+    let name = if source_id.is_crates_io() {
+        "crates-io".to_owned()
+    } else {
+        source_id.without_precise().as_url().to_string()
+    };
+
+    if source_id.is_git() {
+        config.insert("git".to_owned(), source_id.url().to_string());
+        match source_id.git_reference() {
+            Some(GitReference::Branch(branch)) => {
+                config.insert("branch".to_owned(), branch.clone());
+            }
+            Some(GitReference::Tag(tag)) => {
+                config.insert("tag".to_owned(), tag.clone());
+            }
+            Some(GitReference::Rev(rev)) => {
+                config.insert("rev".to_owned(), rev.clone());
+            }
+            Some(GitReference::DefaultBranch) | None => {}
+        }
+    } else if !source_id.is_crates_io() {
+        config.insert("registry".to_owned(), source_id.url().to_string());
+    }
+    config.insert("replace-with".to_owned(), VENDORED_SOURCES.to_owned());
+
+    VendorOut::Source { name, config }
+}
@@ -0,0 +1,145 @@
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::Context as _;
+use cargo::core::SourceKind;
+use cargo::util::Filesystem;
+use cargo::{CargoResult, GlobalContext};
+use cargo_plumbing_schemas::lockfile::NormalizedDependency;
+use cargo_plumbing_schemas::write_lockfile::WriteLockfileIn;
+use url::Url;
+
+use crate::ops::check_message_format_version;
+
+/// Writes the lockfile described by `input` to `lockfile_path`.
+pub fn write_lockfile(
+    gctx: &GlobalContext,
+    lockfile_path: &Path,
+    input: impl IntoIterator<Item = WriteLockfileIn>,
+) -> CargoResult<()> {
+    let lock_path = gctx.cwd().join(lockfile_path);
+    let file_name = lock_path
+        .file_name()
+        .with_context(|| format!("lockfile path `{}` has no file name", lock_path.display()))?;
+
+    let mut lock_version: Option<u32> = None;
+    let mut locked_packages = Vec::new();
+    let mut unused_patches = None;
+
+    for message in input {
+        match message {
+            WriteLockfileIn::SchemaVersion { version } => check_message_format_version(version)?,
+            WriteLockfileIn::Lockfile { version } => lock_version = version,
+            WriteLockfileIn::LockedPackage { package } => locked_packages.push(package),
+            WriteLockfileIn::UnusedPatches { unused } => unused_patches = Some(unused),
+        }
+    }
+
+    let root = lock_path.parent().expect("Lockfile path can't be root");
+    let lock_root = Filesystem::new(root.to_owned());
+
+    let mut out = String::new();
+
+    let marker_line = "# This file is automatically @generated by Cargo.";
+    let extra_line = "# It is not intended for manual editing.";
+    out.push_str(marker_line);
+    out.push('\n');
+    out.push_str(extra_line);
+    out.push('\n');
+
+    if let Some(version) = lock_version {
+        out.push_str(&format!("version = {version}\n\n"));
+    }
+
+    for package in locked_packages {
+        out.push_str("[[package]]\n");
+        emit_package(package, &mut out);
+    }
+
+    if let Some(unused_patches) = unused_patches {
+        for patch in unused_patches.unused {
+            out.push_str("[[patch.unused]]\n");
+            emit_package(patch, &mut out);
+        }
+    }
+
+    if let Some(v) = lock_version {
+        if v >= 2 {
+            while out.ends_with("\n\n") {
+                out.pop();
+            }
+        }
+    }
+
+    lock_root
+        .open_rw_exclusive_create(file_name, gctx, "lockfile")
+        .and_then(|mut f| {
+            f.file().set_len(0)?;
+            f.write_all(out.as_bytes())?;
+            Ok(())
+        })
+        .with_context(|| format!("failed to write {}", lock_path.display()))?;
+
+    Ok(())
+}
+
+fn emit_package(package: NormalizedDependency, out: &mut String) {
+    out.push_str(&format!("name = \"{}\"\n", package.id.name()));
+
+    if let Some(version) = package.id.version() {
+        out.push_str(&format!("version = \"{version}\"\n"));
+    }
+
+    if let Some(url) = package.id.url() {
+        if let Some(kind) = package.id.kind() {
+            out.push_str("source = \"");
+            emit_source_value(url, kind, &package.rev, out);
+            out.push_str("\"\n");
+        }
+    }
+
+    if let Some(checksum) = &package.checksum {
+        out.push_str(&format!("checksum = \"{checksum}\"\n"));
+    }
+
+    if let Some(deps) = &package.dependencies {
+        if !deps.is_empty() {
+            out.push_str("dependencies = [\n");
+            for dep in deps {
+                out.push_str(&format!(r#" "{}"#, dep.name()));
+                if let Some(version) = dep.version() {
+                    out.push_str(&format!(" {version}"));
+                }
+                if let Some(url) = dep.url() {
+                    if let Some(kind) = dep.kind() {
+                        out.push_str(" (");
+                        emit_source_value(url, kind, &None, out);
+                        out.push(')');
+                    }
+                }
+                out.push_str("\",\n");
+            }
+            out.push_str("]\n");
+        }
+    }
+
+    out.push('\n');
+}
+
+fn emit_source_value(url: &Url, kind: &SourceKind, rev: &Option<String>, out: &mut String) {
+    if let Some(protocol) = kind.protocol() {
+        out.push_str(&format!("{protocol}+"));
+    }
+    out.push_str(url.as_str());
+    if let Some(query) = url.query() {
+        out.push_str(&format!("?{query}"));
+    }
+    if let SourceKind::Git(git_ref) = kind {
+        if let Some(pretty) = git_ref.pretty_ref(true) {
+            out.push_str(&format!("?{pretty}"));
+        }
+    }
+    if let Some(rev) = rev {
+        out.push_str(&format!("#{rev}"));
+    }
+}
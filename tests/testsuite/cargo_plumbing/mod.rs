@@ -1 +1,3 @@
+// `schema` is only listed with the `unstable-schema` feature.
+#[cfg(feature = "unstable-schema")]
 mod help;
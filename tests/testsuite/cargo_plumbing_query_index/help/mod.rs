@@ -0,0 +1,16 @@
+use cargo_test_support::file;
+use cargo_test_support::prelude::*;
+
+use crate::CargoCommandExt;
+
+#[cargo_test]
+fn case() {
+    snapbox::cmd::Command::cargo_ui()
+        .arg("plumbing")
+        .arg("query-index")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout_eq(file!["stdout.term.svg"])
+        .stderr_eq(file!["stderr.term.svg"]);
+}
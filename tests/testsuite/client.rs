@@ -0,0 +1,112 @@
+use cargo_plumbing_schemas::client::PlumbingCommand;
+use cargo_plumbing_schemas::locate_manifest::LocateManifestOut;
+use cargo_plumbing_schemas::read_lockfile::ReadLockfileOut;
+use cargo_plumbing_schemas::write_lockfile::WriteLockfileIn;
+use cargo_test_macro::cargo_test;
+use cargo_test_support::compare::assert_e2e;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str};
+
+use crate::{cargo_plumbing_exe, ProjectExt};
+
+#[cargo_test]
+fn locate_manifest() {
+    let p = project().file("src/lib.rs", "").build();
+
+    let messages = PlumbingCommand::locate_manifest()
+        .program(cargo_plumbing_exe())
+        .current_dir(p.root().join("src"))
+        .output()
+        .unwrap()
+        .into_messages()
+        .unwrap();
+
+    assert_eq!(messages.len(), 1);
+    let LocateManifestOut::ManifestLocation { manifest_path } = &messages[0] else {
+        panic!("expected a manifest location");
+    };
+    assert_eq!(manifest_path, &p.root().join("Cargo.toml"));
+}
+
+#[cargo_test]
+fn pipes_input_to_write_lockfile() {
+    Package::new("a", "1.0.0").publish();
+    Package::new("b", "1.0.0").dep("a", "1.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "client-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                b = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+    let lockfile_path = p.root().join("Cargo.lock");
+    let original = p.read_lockfile();
+
+    let messages = PlumbingCommand::read_lockfile(&lockfile_path)
+        .program(cargo_plumbing_exe())
+        .output()
+        .unwrap()
+        .into_messages()
+        .unwrap();
+    let input = messages
+        .into_iter()
+        .map(|msg| match msg {
+            ReadLockfileOut::SchemaVersion { version } => {
+                WriteLockfileIn::SchemaVersion { version }
+            }
+            ReadLockfileOut::Lockfile { version } => WriteLockfileIn::Lockfile {
+                version: Some(version),
+            },
+            ReadLockfileOut::LockedPackage { package } => {
+                WriteLockfileIn::LockedPackage { package }
+            }
+            ReadLockfileOut::UnusedPatches { unused } => WriteLockfileIn::UnusedPatches { unused },
+            ReadLockfileOut::SourceReplacement { .. } => {
+                unreachable!("source replacements aren't requested")
+            }
+        })
+        .collect::<Vec<_>>();
+
+    std::fs::remove_file(&lockfile_path).unwrap();
+    let out = PlumbingCommand::write_lockfile(&lockfile_path)
+        .program(cargo_plumbing_exe())
+        .output_with_input(input)
+        .unwrap();
+    assert!(out.status.success(), "{}", out.stderr);
+
+    assert_eq!(p.read_lockfile(), original);
+}
+
+#[cargo_test]
+fn captures_stderr_on_failure() {
+    let p = project().file("src/lib.rs", "").build();
+
+    let out = PlumbingCommand::read_lockfile(p.root().join("foo.lock"))
+        .program(cargo_plumbing_exe())
+        .output()
+        .unwrap();
+
+    assert!(!out.status.success());
+    assert!(out.messages.is_empty());
+    assert_e2e().eq(
+        &out.stderr,
+        str![[r#"
+[ERROR] failed to open: [ROOT]/foo/foo.lock
+
+Caused by:
+  [NOT_FOUND]
+
+"#]],
+    );
+}
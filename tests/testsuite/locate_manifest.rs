@@ -1,3 +1,4 @@
+use cargo_plumbing_schemas::locate_manifest::LocateManifestOut;
 use cargo_test_support::prelude::*;
 use cargo_test_support::project;
 use cargo_test_support::str;
@@ -158,6 +159,171 @@ fn finds_member_manifest() {
         .run();
 }
 
+#[cargo_test]
+fn finds_workspace_root_of_member() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                resolver = "3"
+                members = ["crates/*"]
+            "#,
+        )
+        .file(
+            "crates/Cargo.toml",
+            r#"
+                [package]
+                name = "crates"
+                version = "0.1.0"
+                edition = "2024"
+            "#,
+        )
+        .file("crates/crate1/src/lib.rs", "")
+        .file(
+            "crates/crate1/Cargo.toml",
+            r#"
+                [package]
+                name = "crate1"
+                version = "0.1.0"
+                edition = "2024"
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing locate-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("crates/crate1/src"))
+        .arg("--workspace")
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "manifest_path": "[ROOT]/foo/crates/crate1/Cargo.toml",
+    "reason": "manifest-location"
+  },
+  {
+    "manifest_path": "[ROOT]/foo/crates/crate1/Cargo.toml",
+    "reason": "manifest-candidate"
+  },
+  {
+    "manifest_path": "[ROOT]/foo/crates/Cargo.toml",
+    "reason": "manifest-candidate"
+  },
+  {
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "reason": "manifest-candidate"
+  },
+  {
+    "lockfile_path": "[ROOT]/foo/Cargo.lock",
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "reason": "workspace-root"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data("")
+        .with_status(0)
+        .run();
+}
+
+#[cargo_test]
+fn finds_workspace_root_of_package() {
+    let p = project().build();
+
+    p.cargo_plumbing("plumbing locate-manifest --workspace")
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "reason": "manifest-location"
+  },
+  {
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "reason": "manifest-candidate"
+  },
+  {
+    "lockfile_path": "[ROOT]/foo/Cargo.lock",
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "reason": "workspace-root"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data("")
+        .with_status(0)
+        .run();
+}
+
+#[cargo_test]
+fn finds_embedded_manifest() {
+    let p = project()
+        .no_manifest()
+        .file("script.rs", "---\n---\n\nfn main() {}\n")
+        .build();
+
+    let out = p
+        .cargo_plumbing("plumbing locate-manifest -Zscript --workspace")
+        .arg("--manifest-path")
+        .arg(p.root().join("script.rs"))
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_status(0)
+        .run();
+
+    let messages = LocateManifestOut::parse_stream(&out.stdout[..])
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(messages.len(), 3);
+    let script = p.root().join("script.rs");
+    let LocateManifestOut::ManifestLocation { manifest_path } = &messages[0] else {
+        panic!("expected a manifest-location message");
+    };
+    let LocateManifestOut::ManifestCandidate {
+        manifest_path: candidate,
+    } = &messages[1]
+    else {
+        panic!("expected a manifest-candidate message");
+    };
+    let LocateManifestOut::WorkspaceRoot {
+        manifest_path: root,
+        lockfile_path,
+    } = &messages[2]
+    else {
+        panic!("expected a workspace-root message");
+    };
+    assert_eq!(manifest_path, &script);
+    assert_eq!(candidate, &script);
+    assert_eq!(root, &script);
+
+    // The lockfile of a script isn't kept next to it.
+    assert_eq!(lockfile_path.file_name(), Some("Cargo.lock"));
+    assert!(!lockfile_path.starts_with(p.root()));
+}
+
+#[cargo_test]
+fn errors_on_embedded_manifest_without_script() {
+    let p = project()
+        .no_manifest()
+        .file("script.rs", "---\n---\n\nfn main() {}\n")
+        .build();
+
+    p.cargo_plumbing("plumbing locate-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("script.rs"))
+        .with_stderr_data(str![[r#"
+[ERROR] embedded manifest `[ROOT]/foo/script.rs` requires `-Zscript`
+
+"#]])
+        .with_stdout_data("")
+        .with_status(101)
+        .run();
+}
+
 #[cargo_test]
 fn errors_when_no_manifest() {
     let p = project().no_manifest().build();
@@ -171,3 +337,43 @@ fn errors_when_no_manifest() {
         .with_status(101)
         .run();
 }
+
+#[cargo_test]
+fn prints_schema_version() {
+    let p = project().build();
+
+    p.cargo_plumbing("plumbing locate-manifest --message-format-version 1")
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "schema-version",
+    "version": 1
+  },
+  {
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "reason": "manifest-location"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data("")
+        .with_status(0)
+        .run();
+}
+
+#[cargo_test]
+fn errors_on_unsupported_message_format_version() {
+    let p = project().build();
+
+    p.cargo_plumbing("plumbing locate-manifest --message-format-version 2")
+        .with_stderr_data(str![[r#"
+[ERROR] unsupported message format version `2`, supported versions: 1
+
+"#]])
+        .with_stdout_data("")
+        .with_status(101)
+        .run();
+}
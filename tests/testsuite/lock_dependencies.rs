@@ -967,3 +967,861 @@ fn lock_dependencies_conservatively_using_previous_lock_with_old_lockfile_versio
         .against_jsonlines(),
     );
 }
+
+#[cargo_test]
+fn reports_resolve_error() {
+    Package::new("a", "1.0.0").publish();
+    Package::new("b", "1.0.0").dep("a", "2.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "lock-dependencies-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                b = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing lock-dependencies")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_status(101)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "candidates": [
+      {
+        "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+        "rejected": "unmatched"
+      }
+    ],
+    "message": "failed to select a version for the requirement `a = \"^2.0.0\"`\ncandidate versions found which didn't match: 1.0.0\nlocation searched: `dummy-registry` index (which is replacing registry `crates-io`)\nrequired by package `b v1.0.0`\n    ... which satisfies dependency `b = \"^1.0.0\"` of package `lock-dependencies-test v0.1.0 ([ROOT]/foo)`\nperhaps a crate was updated and forgotten to be re-vendored?",
+    "package_path": [
+      "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+      "path+[ROOTURL]/foo#lock-dependencies-test@0.1.0"
+    ],
+    "reason": "resolve-error",
+    "requirements": [
+      {
+        "name": "a",
+        "package": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+        "req": "^2.0.0"
+      }
+    ]
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data(str![[r#"
+[UPDATING] `dummy-registry` index
+[ERROR] failed to select a version for the requirement `a = "^2.0.0"`
+candidate versions found which didn't match: 1.0.0
+location searched: `dummy-registry` index (which is replacing registry `crates-io`)
+required by package `b v1.0.0`
+    ... which satisfies dependency `b = "^1.0.0"` of package `lock-dependencies-test v0.1.0 ([ROOT]/foo)`
+perhaps a crate was updated and forgotten to be re-vendored?
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn reports_resolve_conflict() {
+    Package::new("a", "1.0.0").publish();
+    Package::new("a", "1.0.1").publish();
+    Package::new("a", "1.0.2").yanked(true).publish();
+    Package::new("b", "1.0.0").dep("a", "=1.0.1").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "lock-dependencies-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                a = "=1.0.0"
+                b = "1.0.0"
+            "#,
+        )
+        .build();
+
+    // `a` is activated before `b`, so it is the requirement of `b` that fails.
+    p.cargo_plumbing("plumbing lock-dependencies")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_status(101)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "candidates": [
+      {
+        "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+        "rejected": "unmatched"
+      },
+      {
+        "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.1",
+        "rejected": "unmatched"
+      },
+      {
+        "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.2",
+        "rejected": "yanked"
+      }
+    ],
+    "message": "failed to select a version for `a`.\n    ... required by package `b v1.0.0`\n    ... which satisfies dependency `b = \"^1.0.0\"` of package `lock-dependencies-test v0.1.0 ([ROOT]/foo)`\nversions that meet the requirements `=1.0.1` are: 1.0.1\n\nall possible versions conflict with previously selected packages.\n\n  previously selected package `a v1.0.0`\n    ... which satisfies dependency `a = \"=1.0.0\"` of package `lock-dependencies-test v0.1.0 ([ROOT]/foo)`\n\nfailed to select a version for `a` which could resolve this conflict",
+    "package_path": [
+      "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+      "path+[ROOTURL]/foo#lock-dependencies-test@0.1.0"
+    ],
+    "reason": "resolve-error",
+    "requirements": [
+      {
+        "name": "a",
+        "package": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+        "req": "=1.0.1"
+      },
+      {
+        "name": "a",
+        "package": "path+[ROOTURL]/foo#lock-dependencies-test@0.1.0",
+        "req": "=1.0.0"
+      }
+    ]
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn partial_lock_with_feature_and_dev_deps_options() {
+    Package::new("a", "1.0.0").publish();
+    Package::new("b", "1.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "lock-dependencies-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                a = { version = "1.0.0", optional = true }
+
+                [dev-dependencies]
+                b = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing lock-dependencies --no-dev-deps")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "partial": true,
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "checksum": "3a351dafbc8a3a9cba7c06dfe8caa11a3a45f800a336bb5b913a8f1e2652d454",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "dependencies": [
+      "a"
+    ],
+    "id": "lock-dependencies-test@0.1.0",
+    "reason": "locked-package"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+
+    p.cargo_plumbing("plumbing lock-dependencies --no-default-features")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "partial": true,
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "checksum": "909035bb08757fa6f58bf655da5337acb736003f7301533602d348a329097837",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "dependencies": [
+      "b"
+    ],
+    "id": "lock-dependencies-test@0.1.0",
+    "reason": "locked-package"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn reports_incompatible_rust_versions() {
+    Package::new("a", "1.0.0").rust_version("1.60").publish();
+    Package::new("a", "1.1.0").rust_version("1.9999").publish();
+    Package::new("b", "1.0.0").rust_version("1.9999").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "lock-dependencies-test"
+                version = "0.1.0"
+                edition = "2024"
+                rust-version = "1.85"
+
+                [dependencies]
+                a = "1.0.0"
+                b = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing lock-dependencies --incompatible-rust-versions fallback")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "checksum": "[..]",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "checksum": "[..]",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "dependencies": [
+      "a",
+      "b"
+    ],
+    "id": "lock-dependencies-test@0.1.0",
+    "reason": "locked-package"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "rust-version-skipped",
+    "rust_version": "1.9999",
+    "version": "1.1.0"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "rust-version-incompatible",
+    "rust_version": "1.9999"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+
+    p.cargo_plumbing("plumbing lock-dependencies --incompatible-rust-versions allow")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "checksum": "[..]",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.1.0",
+    "reason": "locked-package"
+  },
+  {
+    "checksum": "[..]",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "dependencies": [
+      "a",
+      "b"
+    ],
+    "id": "lock-dependencies-test@0.1.0",
+    "reason": "locked-package"
+  },
+  {
+    "compatible_version": "1.0.0",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.1.0",
+    "reason": "rust-version-incompatible",
+    "rust_version": "1.9999"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "rust-version-incompatible",
+    "rust_version": "1.9999"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn reports_no_rust_versions_skipped_by_previous_lock() {
+    Package::new("a", "1.0.0").rust_version("1.60").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "lock-dependencies-test"
+                version = "0.1.0"
+                edition = "2024"
+                rust-version = "1.85"
+
+                [dependencies]
+                a = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+    Package::new("a", "1.1.0").rust_version("1.9999").publish();
+
+    let out = p
+        .cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .run();
+    let previous_lock_input: String = ReadLockfileOut::parse_stream(&out.stdout[..])
+        .filter_map(Result::ok)
+        .filter(|msg| matches!(msg, ReadLockfileOut::LockedPackage { .. }))
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+
+    p.cargo_plumbing("plumbing lock-dependencies --incompatible-rust-versions fallback")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_stdin(previous_lock_input)
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "checksum": "[..]",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "dependencies": [
+      "a"
+    ],
+    "id": "lock-dependencies-test@0.1.0",
+    "reason": "locked-package"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn lock_with_rust_version() {
+    Package::new("a", "1.0.0").rust_version("1.60").publish();
+    Package::new("a", "1.1.0").rust_version("1.80").publish();
+    Package::new("b", "1.0.0")
+        .rust_version("1.60")
+        .dep("c", "1.0.0")
+        .publish();
+    Package::new("c", "1.0.0").rust_version("1.60").publish();
+    Package::new("c", "1.1.0").rust_version("1.80").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "lock-dependencies-test"
+                version = "0.1.0"
+                edition = "2024"
+                rust-version = "1.85"
+
+                [dependencies]
+                a = "1.0.0"
+                b = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing lock-dependencies --rust-version 1.75")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "checksum": "[..]",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "checksum": "[..]",
+    "dependencies": [
+      "c"
+    ],
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "checksum": "[..]",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#c@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "dependencies": [
+      "a",
+      "b"
+    ],
+    "id": "lock-dependencies-test@0.1.0",
+    "reason": "locked-package"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "rust-version-skipped",
+    "rust_version": "1.80",
+    "version": "1.1.0"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#c@1.0.0",
+    "reason": "rust-version-skipped",
+    "rust_version": "1.80",
+    "version": "1.1.0"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn lock_minimal_versions() {
+    Package::new("a", "1.0.0").publish();
+    Package::new("a", "1.0.1").publish();
+    Package::new("b", "1.0.0").dep("a", "1.0.0").publish();
+    Package::new("b", "1.0.1").dep("a", "1.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "lock-dependencies-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                b = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing lock-dependencies --minimal-versions")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .masquerade_as_nightly_cargo(&["minimal-versions"])
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "checksum": "3a351dafbc8a3a9cba7c06dfe8caa11a3a45f800a336bb5b913a8f1e2652d454",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "checksum": "[..]",
+    "dependencies": [
+      "a"
+    ],
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "dependencies": [
+      "b"
+    ],
+    "id": "lock-dependencies-test@0.1.0",
+    "reason": "locked-package"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+
+    p.cargo_plumbing("plumbing lock-dependencies --direct-minimal-versions")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .masquerade_as_nightly_cargo(&["direct-minimal-versions"])
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "checksum": "[..]",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.1",
+    "reason": "locked-package"
+  },
+  {
+    "checksum": "[..]",
+    "dependencies": [
+      "a"
+    ],
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "dependencies": [
+      "b"
+    ],
+    "id": "lock-dependencies-test@0.1.0",
+    "reason": "locked-package"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn minimal_versions_requires_nightly() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "lock-dependencies-test"
+                version = "0.1.0"
+                edition = "2024"
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing lock-dependencies --minimal-versions")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] `--minimal-versions` is only available on the nightly channel of Cargo
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn reports_yanked_packages() {
+    Package::new("a", "1.0.0").publish();
+    Package::new("b", "1.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "lock-dependencies-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                a = "1.0.0"
+                b = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+    Package::new("a", "1.0.0").yanked(true).publish();
+
+    let out = p
+        .cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .run();
+    let previous_lock_input: String = ReadLockfileOut::parse_stream(&out.stdout[..])
+        .filter_map(Result::ok)
+        .filter(|msg| matches!(msg, ReadLockfileOut::LockedPackage { .. }))
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+
+    p.cargo_plumbing("plumbing lock-dependencies --report-yanked")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_stdin(previous_lock_input)
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "checksum": "3a351dafbc8a3a9cba7c06dfe8caa11a3a45f800a336bb5b913a8f1e2652d454",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "checksum": "909035bb08757fa6f58bf655da5337acb736003f7301533602d348a329097837",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "dependencies": [
+      "a",
+      "b"
+    ],
+    "id": "lock-dependencies-test@0.1.0",
+    "reason": "locked-package"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "yanked"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn reports_patches() {
+    Package::new("a", "1.0.0").publish();
+    Package::new("b", "1.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "lock-dependencies-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                a = "1.0.0"
+                b = "1.0.0"
+
+                [patch.crates-io]
+                a = { path = "a" }
+
+                [replace]
+                "b:1.0.0" = { path = "b" }
+            "#,
+        )
+        .file("a/Cargo.toml", &basic_manifest("a", "1.0.1"))
+        .file("a/src/lib.rs", "")
+        .file("b/Cargo.toml", &basic_manifest("b", "1.0.0"))
+        .file("b/src/lib.rs", "")
+        .build();
+
+    p.cargo_plumbing("plumbing lock-dependencies --report-patches")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "id": "a@1.0.1",
+    "reason": "locked-package"
+  },
+  {
+    "id": "b@1.0.0",
+    "reason": "locked-package"
+  },
+  {
+    "checksum": "909035bb08757fa6f58bf655da5337acb736003f7301533602d348a329097837",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "locked-package",
+    "replace": "b@1.0.0"
+  },
+  {
+    "dependencies": [
+      "a",
+      "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0"
+    ],
+    "id": "lock-dependencies-test@0.1.0",
+    "reason": "locked-package"
+  },
+  {
+    "dependencies": [
+      {
+        "package": "lock-dependencies-test@0.1.0",
+        "req": "^1.0.0"
+      }
+    ],
+    "id": "path+[ROOTURL]/foo/a#1.0.1",
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "patch_source": "[ROOTURL]/foo/a",
+    "reason": "patch",
+    "source": "https://github.com/rust-lang/crates.io-index"
+  },
+  {
+    "dependencies": [
+      {
+        "package": "lock-dependencies-test@0.1.0",
+        "req": "^1.0.0"
+      }
+    ],
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "reason": "replace",
+    "replace_with": "path+[ROOTURL]/foo/b#1.0.0"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn embedded_manifest() {
+    Package::new("a", "1.0.0").publish();
+
+    let p = project()
+        .no_manifest()
+        .file(
+            "script.rs",
+            r#"---
+[dependencies]
+a = "1.0.0"
+---
+
+fn main() {}
+"#,
+        )
+        .build();
+
+    let manifest_path = p.root().join("script.rs");
+    p.cargo_plumbing("plumbing lock-dependencies -Zscript")
+        .args(&["--manifest-path", manifest_path.to_str().unwrap()])
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "reason": "locked-package",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "checksum": "[..]"
+  },
+  {
+    "reason": "locked-package",
+    "id": "script@0.0.0",
+    "dependencies": [
+      "a"
+    ]
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
@@ -1,18 +1,29 @@
 mod cargo_plumbing;
 mod cargo_plumbing_locate_manifest;
 mod cargo_plumbing_lock_dependencies;
+mod cargo_plumbing_query_index;
 mod cargo_plumbing_read_lockfile;
 mod cargo_plumbing_read_manifest;
 mod cargo_plumbing_resolve_features;
+#[cfg(feature = "unstable-schema")]
+mod cargo_plumbing_schema;
+mod cargo_plumbing_validate;
+mod cargo_plumbing_vendor;
 mod cargo_plumbing_write_lockfile;
 mod check;
+mod client;
 mod locate_manifest;
 mod lock_dependencies;
 mod metadata;
 mod plan_build;
+mod query_index;
 mod read_lockfile;
 mod read_manifest;
 mod resolve_features;
+#[cfg(feature = "unstable-schema")]
+mod schema;
+mod validate;
+mod vendor;
 mod write_lockfile;
 
 use cargo_test_support::{execs, process, ArgLineCommandExt, Execs, Project, TestEnvCommandExt};
@@ -80,7 +80,23 @@ fn workspace() {
         "repository": null,
         "rust_version": null,
         "source": null,
-        "targets": [],
+        "targets": [
+          {
+            "crate_types": [
+              "bin"
+            ],
+            "doc": true,
+            "doctest": false,
+            "edition": "2024",
+            "harness": true,
+            "kind": [
+              "bin"
+            ],
+            "name": "read-manifest-test",
+            "src_path": "[ROOT]/foo/src/main.rs",
+            "test": true
+          }
+        ],
         "version": "0.1.0"
       },
       {
@@ -106,7 +122,23 @@ fn workspace() {
         "repository": null,
         "rust_version": null,
         "source": null,
-        "targets": [],
+        "targets": [
+          {
+            "crate_types": [
+              "lib"
+            ],
+            "doc": true,
+            "doctest": true,
+            "edition": "2024",
+            "harness": true,
+            "kind": [
+              "lib"
+            ],
+            "name": "crate1",
+            "src_path": "[ROOT]/foo/crate1/src/lib.rs",
+            "test": true
+          }
+        ],
         "version": "0.1.0"
       },
       {
@@ -132,7 +164,23 @@ fn workspace() {
         "repository": null,
         "rust_version": null,
         "source": null,
-        "targets": [],
+        "targets": [
+          {
+            "crate_types": [
+              "lib"
+            ],
+            "doc": true,
+            "doctest": true,
+            "edition": "2024",
+            "harness": true,
+            "kind": [
+              "lib"
+            ],
+            "name": "crate2",
+            "src_path": "[ROOT]/foo/crate2/src/lib.rs",
+            "test": true
+          }
+        ],
         "version": "0.1.0"
       }
     ],
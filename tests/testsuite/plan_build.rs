@@ -1,3 +1,5 @@
+use cargo_plumbing_schemas::lock_dependencies::LockDependenciesOut;
+use cargo_plumbing_schemas::plan_build::PlanBuildOut;
 use cargo_plumbing_schemas::read_lockfile::ReadLockfileOut;
 use cargo_plumbing_schemas::read_manifest::ReadManifestOut;
 use cargo_test_support::prelude::*;
@@ -1453,3 +1455,95 @@ fn package_examples_different_intents() {
         )
         .run();
 }
+
+#[cargo_test]
+fn embedded_manifest() {
+    Package::new("a", "1.0.0").publish();
+
+    let p = project()
+        .no_manifest()
+        .file(
+            "script.rs",
+            r#"---
+[dependencies]
+a = "1.0.0"
+---
+
+fn main() {}
+"#,
+        )
+        .build();
+    let manifest_path = p.root().join("script.rs");
+
+    // Scripts keep their lockfile out of their directory, so the lock is taken from
+    // `lock-dependencies` instead.
+    let mut stdin = String::new();
+    let out = p
+        .cargo_plumbing("plumbing read-manifest -Zscript")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_status(0)
+        .run();
+    let out: String = ReadManifestOut::parse_stream(&*out.stdout)
+        .filter_map(Result::ok)
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+    stdin.push_str(&out);
+    stdin.push('\n');
+    let out = p
+        .cargo_plumbing("plumbing lock-dependencies -Zscript")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_status(0)
+        .run();
+    let out: String = LockDependenciesOut::parse_stream(&*out.stdout)
+        .filter_map(Result::ok)
+        .filter(|msg| matches!(msg, LockDependenciesOut::LockedPackage { .. }))
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+    stdin.push_str(&out);
+    stdin.push('\n');
+    let out = p
+        .cargo_plumbing("plumbing resolve-features -Zscript")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stdin(stdin.clone())
+        .with_status(0)
+        .run();
+    stdin.push_str(&String::from_utf8(out.stdout).unwrap());
+    stdin.push('\n');
+
+    let out = p
+        .cargo_plumbing("plumbing plan-build -Zscript")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stdin(stdin)
+        .with_status(0)
+        .run();
+
+    let mut units = PlanBuildOut::parse_stream(&*out.stdout)
+        .map(|msg| msg.unwrap())
+        .filter_map(|msg| match msg {
+            PlanBuildOut::Unit {
+                target, root, deps, ..
+            } => Some((target.name, target.kind, root, deps.len())),
+            PlanBuildOut::SchemaVersion { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    units.sort();
+    assert_eq!(
+        units,
+        [
+            ("a".to_owned(), vec!["lib".to_owned()], false, 0),
+            ("script".to_owned(), vec!["bin".to_owned()], true, 1),
+        ]
+    );
+}
@@ -0,0 +1,112 @@
+use cargo_test_support::prelude::*;
+use cargo_test_support::project;
+use cargo_test_support::registry::{Dependency, Package};
+use cargo_test_support::str;
+
+use crate::ProjectExt;
+
+#[cargo_test]
+fn query_versions_matching_requirement() {
+    Package::new("a", "1.0.0").publish();
+    Package::new("b", "0.9.0").publish();
+    Package::new("b", "1.0.0")
+        .rust_version("1.60")
+        .dep("a", "1.0.0")
+        .add_dep(Dependency::new("c", "1.0.0").optional(true))
+        .add_dep(Dependency::new("d", "1.0.0").dev())
+        .feature("default", &["c"])
+        .publish();
+    Package::new("b", "1.1.0").yanked(true).publish();
+
+    let p = project().build();
+
+    p.cargo_plumbing("plumbing query-index b@1.0")
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "checksum": "[..]",
+    "dependencies": [
+      {
+        "default_features": true,
+        "name": "a",
+        "req": "^1.0.0"
+      },
+      {
+        "default_features": true,
+        "name": "c",
+        "optional": true,
+        "req": "^1.0.0"
+      },
+      {
+        "default_features": true,
+        "kind": "dev",
+        "name": "d",
+        "req": "^1.0.0"
+      }
+    ],
+    "features": {
+      "c": [
+        "dep:c"
+      ],
+      "default": [
+        "c"
+      ]
+    },
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "index-entry",
+    "rust_version": "1.60"
+  },
+  {
+    "checksum": "[..]",
+    "dependencies": [],
+    "features": {},
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.1.0",
+    "reason": "index-entry",
+    "yanked": true
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+
+    p.cargo_plumbing("plumbing query-index b@=1.1.0")
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "checksum": "[..]",
+    "dependencies": [],
+    "features": {},
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.1.0",
+    "reason": "index-entry",
+    "yanked": true
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn errors_on_unknown_package() {
+    Package::new("a", "1.0.0").publish();
+
+    let p = project().build();
+
+    p.cargo_plumbing("plumbing query-index a@2")
+        .with_status(101)
+        .with_stdout_data("")
+        .with_stderr_data(str![[r#"
+[UPDATING] [..] index
+[ERROR] could not find `a@2` in registry `crates-io`
+
+"#]])
+        .run();
+}
@@ -1,5 +1,5 @@
 use cargo_test_macro::cargo_test;
-use cargo_test_support::registry::{Package, RegistryBuilder};
+use cargo_test_support::registry::{registry_path, Package, RegistryBuilder};
 use cargo_test_support::{basic_manifest, git, project, str};
 use snapbox::IntoData;
 
@@ -476,6 +476,47 @@ fn workspace_member_with_inherited_deps() {
         .run();
 }
 
+#[cargo_test]
+fn reads_workspace_lockfile_by_default() {
+    let p = project()
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                resolver = "3"
+                members = ["crate1"]
+            "#,
+        )
+        .file("crate1/src/lib.rs", "")
+        .file("crate1/Cargo.toml", &basic_manifest("crate1", "0.1.0"))
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+
+    // The lockfile is found from the member manifest, as it is shared by the workspace.
+    p.cargo_plumbing("plumbing read-lockfile")
+        .arg("--manifest-path")
+        .arg(p.root().join("crate1/Cargo.toml"))
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "reason": "locked-package",
+    "id": "crate1@0.1.0"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
 #[cargo_test]
 fn workspace_package_depend_on_workspace_member() {
     Package::new("a", "1.0.0").publish();
@@ -742,10 +783,10 @@ fn bad_lockfile_invalid_semver() {
 }
 
 #[cargo_test]
-fn invalid_lockfile_name() {
+fn custom_lockfile_name() {
     let p = project()
         .file(
-            "invalid-Cargo.lock",
+            "custom.lock",
             r#"
                 version = 4
 
@@ -758,12 +799,24 @@ fn invalid_lockfile_name() {
 
     p.cargo_plumbing("plumbing read-lockfile")
         .arg("--lockfile-path")
-        .arg(p.root().join("invalid-Cargo.lock"))
-        .with_status(101)
-        .with_stderr_data(str![[r#"
-[ERROR] lockfile name should be `Cargo.lock`
-
-"#]])
+        .arg(p.root().join("custom.lock"))
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "reason": "locked-package",
+    "id": "read-lockfile-test@0.1.0"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
         .run();
 }
 
@@ -893,3 +946,202 @@ fn output_packages_in_lexicographical_order_old_lockfile() {
         )
         .run();
 }
+
+#[cargo_test]
+fn reports_source_replacements() {
+    Package::new("a", "1.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "read-lockfile-test"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+
+                [dependencies]
+                a = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+    std::fs::create_dir_all(p.root().join(".cargo")).unwrap();
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [source.crates-io]
+            replace-with = "vendored-sources"
+
+            [source.vendored-sources]
+            directory = "vendor"
+        "#,
+    );
+
+    p.cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .arg("--report-source-replacements")
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "reason": "locked-package",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "checksum": "3a351dafbc8a3a9cba7c06dfe8caa11a3a45f800a336bb5b913a8f1e2652d454"
+  },
+  {
+    "reason": "locked-package",
+    "id": "read-lockfile-test@0.1.0",
+    "dependencies": [
+      "a"
+    ]
+  },
+  {
+    "reason": "source-replacement",
+    "source": "registry+https://github.com/rust-lang/crates.io-index",
+    "replace_with": "directory+[ROOTURL]/foo/vendor"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn reports_local_registry_replacements() {
+    Package::new("a", "1.0.0").local(true).publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "read-lockfile-test"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+
+                [dependencies]
+                a = "1.0.0"
+            "#,
+        )
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [source.crates-io]
+                    replace-with = "local-sources"
+
+                    [source.local-sources]
+                    local-registry = '{}'
+                "#,
+                registry_path().display(),
+            ),
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+
+    p.cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .arg("--report-source-replacements")
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "reason": "locked-package",
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "checksum": "[..]"
+  },
+  {
+    "reason": "locked-package",
+    "id": "read-lockfile-test@0.1.0",
+    "dependencies": [
+      "a"
+    ]
+  },
+  {
+    "reason": "source-replacement",
+    "source": "registry+https://github.com/rust-lang/crates.io-index",
+    "replace_with": "local-registry+[ROOTURL]/registry"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn package_with_alternative_registry_deps() {
+    let _registry = RegistryBuilder::new().alternative().build();
+    Package::new("a", "1.0.0").alternative(true).publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "read-lockfile-test"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+
+                [dependencies]
+                a = { version = "1.0.0", registry = "alternative" }
+            "#,
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+
+    p.cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "reason": "lockfile",
+    "version": 4
+  },
+  {
+    "reason": "locked-package",
+    "id": "registry+[ROOTURL]/alternative-registry#a@1.0.0",
+    "checksum": "[..]"
+  },
+  {
+    "reason": "locked-package",
+    "id": "read-lockfile-test@0.1.0",
+    "dependencies": [
+      "a"
+    ]
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
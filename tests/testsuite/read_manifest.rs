@@ -1,6 +1,7 @@
+use cargo_plumbing_schemas::read_lockfile::ReadLockfileOut;
 use cargo_test_macro::cargo_test;
 use cargo_test_support::registry::Package;
-use cargo_test_support::{main_file, project, str};
+use cargo_test_support::{basic_manifest, main_file, project, str};
 use snapbox::IntoData;
 
 use crate::ProjectExt;
@@ -2241,3 +2242,1851 @@ fn workspace_member_via_package_workspace_key() {
         .with_status(0)
         .run();
 }
+
+#[cargo_test]
+fn workspace_member_original_manifest() {
+    Package::new("a", "1.0.0").publish();
+
+    let p = project()
+        .file("crate1/src/lib.rs", "")
+        .file(
+            "crate1/Cargo.toml",
+            r#"
+                [package]
+                name = "crate1"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+
+                [dependencies]
+                a.workspace = true
+            "#,
+        )
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                resolver = "3"
+                members = ["crate1"]
+
+                [workspace.dependencies]
+                a = "1.0.0"
+            "#,
+        )
+        .build();
+
+    // The normalized manifest has the inherited dependency filled in, while the original one
+    // still refers to the workspace.
+    p.cargo_plumbing("plumbing read-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("crate1/Cargo.toml"))
+        .arg("--original")
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "document": "\n                [package]\n                name = \"crate1\"\n                version = \"0.1.0\"\n                authors = []\n                edition = \"2024\"\n\n                [dependencies]\n                a.workspace = true\n            ",
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": [],
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": {
+        "a": {
+          "artifact": null,
+          "base": null,
+          "branch": null,
+          "default-features": null,
+          "default_features": null,
+          "features": null,
+          "git": null,
+          "lib": null,
+          "optional": null,
+          "package": null,
+          "path": null,
+          "public": null,
+          "registry": null,
+          "registry-index": null,
+          "rev": null,
+          "tag": null,
+          "target": null,
+          "version": "1.0.0"
+        }
+      },
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [],
+      "features": null,
+      "hints": null,
+      "lib": {
+        "bench": null,
+        "crate-type": null,
+        "crate_type": null,
+        "doc": null,
+        "doc-scrape-examples": null,
+        "doctest": null,
+        "edition": null,
+        "filename": null,
+        "harness": null,
+        "name": "crate1",
+        "path": "src/lib.rs",
+        "proc-macro": null,
+        "proc_macro": null,
+        "required-features": null,
+        "test": null
+      },
+      "lints": null,
+      "package": {
+        "authors": [],
+        "autobenches": false,
+        "autobins": false,
+        "autoexamples": false,
+        "autolib": false,
+        "autotests": false,
+        "build": false,
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": null,
+        "name": "crate1",
+        "publish": null,
+        "readme": false,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "0.1.0",
+        "workspace": null
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [],
+      "workspace": null
+    },
+    "original": {
+      "badges": null,
+      "bench": null,
+      "bin": null,
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": {
+        "a": {
+          "default-features": null,
+          "default_features": null,
+          "features": null,
+          "optional": null,
+          "public": null,
+          "workspace": true
+        }
+      },
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": null,
+      "features": null,
+      "hints": null,
+      "lib": null,
+      "lints": null,
+      "package": {
+        "authors": [],
+        "autobenches": null,
+        "autobins": null,
+        "autoexamples": null,
+        "autolib": null,
+        "autotests": null,
+        "build": null,
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": null,
+        "name": "crate1",
+        "publish": null,
+        "readme": null,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "0.1.0",
+        "workspace": null
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": null,
+      "workspace": null
+    },
+    "path": "[ROOT]/foo/crate1/Cargo.toml",
+    "pkg_id": "path+[ROOTURL]/foo/crate1#0.1.0",
+    "reason": "manifest"
+  },
+  {
+    "document": "\n                [workspace]\n                resolver = \"3\"\n                members = [\"crate1\"]\n\n                [workspace.dependencies]\n                a = \"1.0.0\"\n            ",
+    "manifest": {
+      "badges": null,
+      "bench": null,
+      "bin": null,
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": null,
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": null,
+      "features": null,
+      "hints": null,
+      "lib": null,
+      "lints": null,
+      "package": null,
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": null,
+      "workspace": {
+        "default-members": null,
+        "dependencies": {
+          "a": "1.0.0"
+        },
+        "exclude": null,
+        "lints": null,
+        "members": [
+          "crate1"
+        ],
+        "metadata": null,
+        "package": null,
+        "resolver": "3"
+      }
+    },
+    "original": {
+      "badges": null,
+      "bench": null,
+      "bin": null,
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": null,
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": null,
+      "features": null,
+      "hints": null,
+      "lib": null,
+      "lints": null,
+      "package": null,
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": null,
+      "workspace": {
+        "default-members": null,
+        "dependencies": {
+          "a": "1.0.0"
+        },
+        "exclude": null,
+        "lints": null,
+        "members": [
+          "crate1"
+        ],
+        "metadata": null,
+        "package": null,
+        "resolver": "3"
+      }
+    },
+    "path": "[ROOT]/foo/Cargo.toml",
+    "pkg_id": null,
+    "reason": "manifest",
+    "workspace": true
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_status(0)
+        .run();
+}
+
+#[cargo_test]
+fn package_manifest_spans() {
+    let manifest = r#"[package]
+name = "read-manifest-test"
+version = "0.1.0"
+edition = "2024"
+metadata = { "with \"quote\" and \u007F" = true }
+
+[dependencies]
+a = { version = "1.0.0" }
+
+[[bin]]
+name = "main"
+path = "src/main.rs"
+"#;
+    let p = project()
+        .file("src/main.rs", &main_file("Hello", &[]))
+        .file("Cargo.toml", manifest)
+        .build();
+
+    // What the tables and arrays of tables span is up to the parser, so only their keys are
+    // pinned down.
+    p.cargo_plumbing("plumbing read-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .arg("--spans")
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": [
+        {
+          "bench": null,
+          "crate-type": null,
+          "crate_type": null,
+          "doc": null,
+          "doc-scrape-examples": null,
+          "doctest": null,
+          "edition": null,
+          "filename": null,
+          "harness": null,
+          "name": "main",
+          "path": "src/main.rs",
+          "proc-macro": null,
+          "proc_macro": null,
+          "required-features": null,
+          "test": null
+        }
+      ],
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": {
+        "a": {
+          "artifact": null,
+          "base": null,
+          "branch": null,
+          "default-features": null,
+          "default_features": null,
+          "features": null,
+          "git": null,
+          "lib": null,
+          "optional": null,
+          "package": null,
+          "path": null,
+          "public": null,
+          "registry": null,
+          "registry-index": null,
+          "rev": null,
+          "tag": null,
+          "target": null,
+          "version": "1.0.0"
+        }
+      },
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [],
+      "features": null,
+      "hints": null,
+      "lib": null,
+      "lints": null,
+      "package": {
+        "authors": null,
+        "autobenches": false,
+        "autobins": false,
+        "autoexamples": false,
+        "autolib": false,
+        "autotests": false,
+        "build": false,
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": {
+          "with \"quote\" and \u007f": true
+        },
+        "name": "read-manifest-test",
+        "publish": null,
+        "readme": false,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "0.1.0",
+        "workspace": null
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [],
+      "workspace": null
+    },
+    "path": "[ROOT]/foo/Cargo.toml",
+    "pkg_id": "path+[ROOTURL]/foo#read-manifest-test@0.1.0",
+    "reason": "manifest",
+    "spans": {
+      "bin": {
+        "key": {
+          "column": 3,
+          "end": 171,
+          "line": 10,
+          "start": 168
+        },
+        "value": "{...}"
+      },
+      "bin[0]": {
+        "value": "{...}"
+      },
+      "bin[0].name": {
+        "key": {
+          "column": 1,
+          "end": 178,
+          "line": 11,
+          "start": 174
+        },
+        "value": {
+          "column": 8,
+          "end": 187,
+          "line": 11,
+          "start": 181
+        }
+      },
+      "bin[0].path": {
+        "key": {
+          "column": 1,
+          "end": 192,
+          "line": 12,
+          "start": 188
+        },
+        "value": {
+          "column": 8,
+          "end": 208,
+          "line": 12,
+          "start": 195
+        }
+      },
+      "dependencies": {
+        "key": {
+          "column": 2,
+          "end": 137,
+          "line": 7,
+          "start": 125
+        },
+        "value": "{...}"
+      },
+      "dependencies.a": {
+        "key": {
+          "column": 1,
+          "end": 140,
+          "line": 8,
+          "start": 139
+        },
+        "value": {
+          "column": 5,
+          "end": 164,
+          "line": 8,
+          "start": 143
+        }
+      },
+      "dependencies.a.version": {
+        "key": {
+          "column": 7,
+          "end": 152,
+          "line": 8,
+          "start": 145
+        },
+        "value": {
+          "column": 17,
+          "end": 162,
+          "line": 8,
+          "start": 155
+        }
+      },
+      "package": {
+        "key": {
+          "column": 2,
+          "end": 8,
+          "line": 1,
+          "start": 1
+        },
+        "value": "{...}"
+      },
+      "package.edition": {
+        "key": {
+          "column": 1,
+          "end": 63,
+          "line": 4,
+          "start": 56
+        },
+        "value": {
+          "column": 11,
+          "end": 72,
+          "line": 4,
+          "start": 66
+        }
+      },
+      "package.metadata": {
+        "key": {
+          "column": 1,
+          "end": 81,
+          "line": 5,
+          "start": 73
+        },
+        "value": {
+          "column": 12,
+          "end": 122,
+          "line": 5,
+          "start": 84
+        }
+      },
+      "package.metadata.\"with \\\"quote\\\" and \\u007F\"": {
+        "key": {
+          "column": 14,
+          "end": 113,
+          "line": 5,
+          "start": 86
+        },
+        "value": {
+          "column": 44,
+          "end": 120,
+          "line": 5,
+          "start": 116
+        }
+      },
+      "package.name": {
+        "key": {
+          "column": 1,
+          "end": 14,
+          "line": 2,
+          "start": 10
+        },
+        "value": {
+          "column": 8,
+          "end": 37,
+          "line": 2,
+          "start": 17
+        }
+      },
+      "package.version": {
+        "key": {
+          "column": 1,
+          "end": 45,
+          "line": 3,
+          "start": 38
+        },
+        "value": {
+          "column": 11,
+          "end": 55,
+          "line": 3,
+          "start": 48
+        }
+      }
+    },
+    "workspace": true
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_status(0)
+        .run();
+}
+
+#[cargo_test]
+fn package_discovered_targets() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file("src/main.rs", &main_file("Hello", &[]))
+        .file("examples/ex.rs", "fn main() {}")
+        .file("tests/it.rs", "")
+        .file("build.rs", "fn main() {}")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "read-manifest-test"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+
+                [[example]]
+                name = "ex"
+                required-features = ["extra"]
+
+                [[test]]
+                name = "it"
+                harness = false
+
+                [features]
+                extra = []
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing read-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .arg("--targets")
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": [
+        {
+          "bench": null,
+          "crate-type": null,
+          "crate_type": null,
+          "doc": null,
+          "doc-scrape-examples": null,
+          "doctest": null,
+          "edition": null,
+          "filename": null,
+          "harness": null,
+          "name": "read-manifest-test",
+          "path": "src/main.rs",
+          "proc-macro": null,
+          "proc_macro": null,
+          "required-features": null,
+          "test": null
+        }
+      ],
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": null,
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [
+        {
+          "bench": null,
+          "crate-type": null,
+          "crate_type": null,
+          "doc": null,
+          "doc-scrape-examples": null,
+          "doctest": null,
+          "edition": null,
+          "filename": null,
+          "harness": null,
+          "name": "ex",
+          "path": "examples/ex.rs",
+          "proc-macro": null,
+          "proc_macro": null,
+          "required-features": [
+            "extra"
+          ],
+          "test": null
+        }
+      ],
+      "features": {
+        "extra": []
+      },
+      "hints": null,
+      "lib": {
+        "bench": null,
+        "crate-type": null,
+        "crate_type": null,
+        "doc": null,
+        "doc-scrape-examples": null,
+        "doctest": null,
+        "edition": null,
+        "filename": null,
+        "harness": null,
+        "name": "read_manifest_test",
+        "path": "src/lib.rs",
+        "proc-macro": null,
+        "proc_macro": null,
+        "required-features": null,
+        "test": null
+      },
+      "lints": null,
+      "package": {
+        "authors": [],
+        "autobenches": false,
+        "autobins": false,
+        "autoexamples": false,
+        "autolib": false,
+        "autotests": false,
+        "build": "build.rs",
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": null,
+        "name": "read-manifest-test",
+        "publish": null,
+        "readme": false,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "0.1.0",
+        "workspace": null
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [
+        {
+          "bench": null,
+          "crate-type": null,
+          "crate_type": null,
+          "doc": null,
+          "doc-scrape-examples": null,
+          "doctest": null,
+          "edition": null,
+          "filename": null,
+          "harness": false,
+          "name": "it",
+          "path": "tests/it.rs",
+          "proc-macro": null,
+          "proc_macro": null,
+          "required-features": null,
+          "test": null
+        }
+      ],
+      "workspace": null
+    },
+    "path": "[ROOT]/foo/Cargo.toml",
+    "pkg_id": "path+[ROOTURL]/foo#read-manifest-test@0.1.0",
+    "reason": "manifest",
+    "targets": [
+      {
+        "crate_types": [
+          "lib"
+        ],
+        "doc": true,
+        "doctest": true,
+        "edition": "2024",
+        "harness": true,
+        "kind": [
+          "lib"
+        ],
+        "name": "read_manifest_test",
+        "src_path": "[ROOT]/foo/src/lib.rs",
+        "test": true
+      },
+      {
+        "crate_types": [
+          "bin"
+        ],
+        "doc": true,
+        "doctest": false,
+        "edition": "2024",
+        "harness": true,
+        "kind": [
+          "bin"
+        ],
+        "name": "read-manifest-test",
+        "src_path": "[ROOT]/foo/src/main.rs",
+        "test": true
+      },
+      {
+        "crate_types": [
+          "bin"
+        ],
+        "doc": false,
+        "doctest": false,
+        "edition": "2024",
+        "harness": true,
+        "kind": [
+          "example"
+        ],
+        "name": "ex",
+        "required-features": [
+          "extra"
+        ],
+        "src_path": "[ROOT]/foo/examples/ex.rs",
+        "test": false
+      },
+      {
+        "crate_types": [
+          "bin"
+        ],
+        "doc": false,
+        "doctest": false,
+        "edition": "2024",
+        "harness": false,
+        "kind": [
+          "test"
+        ],
+        "name": "it",
+        "src_path": "[ROOT]/foo/tests/it.rs",
+        "test": true
+      },
+      {
+        "crate_types": [
+          "bin"
+        ],
+        "doc": false,
+        "doctest": false,
+        "edition": "2024",
+        "harness": true,
+        "kind": [
+          "custom-build"
+        ],
+        "name": "build-script-build",
+        "src_path": "[ROOT]/foo/build.rs",
+        "test": false
+      }
+    ],
+    "workspace": true
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_status(0)
+        .run();
+}
+
+#[cargo_test]
+fn embedded_manifest() {
+    Package::new("a", "1.0.0").publish();
+
+    let p = project()
+        .no_manifest()
+        .file(
+            "script.rs",
+            r#"---
+[dependencies]
+a = "1.0.0"
+---
+
+fn main() {}
+"#,
+        )
+        .build();
+
+    // Scripts are their own workspace. The package, its bin target and the workspace are
+    // derived by Cargo from the script, so only the dependencies are checked in full.
+    p.cargo_plumbing("plumbing read-manifest -Zscript")
+        .arg("--manifest-path")
+        .arg(p.root().join("script.rs"))
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "embedded": true,
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": "{...}",
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": {
+        "a": {
+          "artifact": null,
+          "base": null,
+          "branch": null,
+          "default-features": null,
+          "default_features": null,
+          "features": null,
+          "git": null,
+          "lib": null,
+          "optional": null,
+          "package": null,
+          "path": null,
+          "public": null,
+          "registry": null,
+          "registry-index": null,
+          "rev": null,
+          "tag": null,
+          "target": null,
+          "version": "1.0.0"
+        }
+      },
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [],
+      "features": null,
+      "hints": null,
+      "lib": null,
+      "lints": null,
+      "package": "{...}",
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [],
+      "workspace": "{...}"
+    },
+    "path": "[ROOT]/foo/script.rs",
+    "pkg_id": "path+[ROOTURL]/foo/script.rs#script@0.0.0",
+    "reason": "manifest",
+    "workspace": true
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_status(0)
+        .run();
+}
+
+#[cargo_test]
+fn package_with_unused_keys() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "read-manifest-test"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+                unused = "value"
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing read-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .arg("--report-warnings")
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": [],
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": null,
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [],
+      "features": null,
+      "hints": null,
+      "lib": {
+        "bench": null,
+        "crate-type": null,
+        "crate_type": null,
+        "doc": null,
+        "doc-scrape-examples": null,
+        "doctest": null,
+        "edition": null,
+        "filename": null,
+        "harness": null,
+        "name": "read_manifest_test",
+        "path": "src/lib.rs",
+        "proc-macro": null,
+        "proc_macro": null,
+        "required-features": null,
+        "test": null
+      },
+      "lints": null,
+      "package": {
+        "authors": [],
+        "autobenches": false,
+        "autobins": false,
+        "autoexamples": false,
+        "autolib": false,
+        "autotests": false,
+        "build": false,
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": null,
+        "name": "read-manifest-test",
+        "publish": null,
+        "readme": false,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "0.1.0",
+        "workspace": null
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [],
+      "workspace": null
+    },
+    "path": "[ROOT]/foo/Cargo.toml",
+    "pkg_id": "path+[ROOTURL]/foo#read-manifest-test@0.1.0",
+    "reason": "manifest",
+    "workspace": true
+  },
+  {
+    "key": "package.unused",
+    "message": "unused manifest key: package.unused",
+    "path": "[ROOT]/foo/Cargo.toml",
+    "reason": "warning"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_status(0)
+        .run();
+}
+
+#[cargo_test]
+fn workspace_membership() {
+    let p = project()
+        .file("src/main.rs", &main_file("Hello", &[]))
+        .file(
+            "Cargo.toml",
+            r#"
+                [workspace]
+                resolver = "3"
+                members = ["crates/*"]
+                default-members = ["crates/a"]
+                exclude = ["crates/excluded"]
+
+                [package]
+                name = "ws-root"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+            "#,
+        )
+        .file("crates/a/src/lib.rs", "")
+        .file(
+            "crates/a/Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+
+                [dependencies]
+                b = { path = "../../libs/b" }
+            "#,
+        )
+        .file("libs/b/src/lib.rs", "")
+        .file(
+            "libs/b/Cargo.toml",
+            r#"
+                [package]
+                name = "b"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+            "#,
+        )
+        .file("crates/excluded/src/lib.rs", "")
+        .file(
+            "crates/excluded/Cargo.toml",
+            &basic_manifest("excluded", "0.1.0"),
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing read-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .arg("--workspace")
+        .arg("--report-workspace")
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": [
+        {
+          "bench": null,
+          "crate-type": null,
+          "crate_type": null,
+          "doc": null,
+          "doc-scrape-examples": null,
+          "doctest": null,
+          "edition": null,
+          "filename": null,
+          "harness": null,
+          "name": "ws-root",
+          "path": "src/main.rs",
+          "proc-macro": null,
+          "proc_macro": null,
+          "required-features": null,
+          "test": null
+        }
+      ],
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": null,
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [],
+      "features": null,
+      "hints": null,
+      "lib": null,
+      "lints": null,
+      "package": {
+        "authors": [],
+        "autobenches": false,
+        "autobins": false,
+        "autoexamples": false,
+        "autolib": false,
+        "autotests": false,
+        "build": false,
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": null,
+        "name": "ws-root",
+        "publish": null,
+        "readme": false,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "0.1.0",
+        "workspace": null
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [],
+      "workspace": {
+        "default-members": [
+          "crates/a"
+        ],
+        "dependencies": null,
+        "exclude": [
+          "crates/excluded"
+        ],
+        "lints": null,
+        "members": [
+          "crates/*"
+        ],
+        "metadata": null,
+        "package": null,
+        "resolver": "3"
+      }
+    },
+    "path": "[ROOT]/foo/Cargo.toml",
+    "pkg_id": "path+[ROOTURL]/foo#ws-root@0.1.0",
+    "reason": "manifest",
+    "workspace": true
+  },
+  {
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": [],
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": {
+        "b": {
+          "artifact": null,
+          "base": null,
+          "branch": null,
+          "default-features": null,
+          "default_features": null,
+          "features": null,
+          "git": null,
+          "lib": null,
+          "optional": null,
+          "package": null,
+          "path": "../../libs/b",
+          "public": null,
+          "registry": null,
+          "registry-index": null,
+          "rev": null,
+          "tag": null,
+          "target": null,
+          "version": null
+        }
+      },
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [],
+      "features": null,
+      "hints": null,
+      "lib": {
+        "bench": null,
+        "crate-type": null,
+        "crate_type": null,
+        "doc": null,
+        "doc-scrape-examples": null,
+        "doctest": null,
+        "edition": null,
+        "filename": null,
+        "harness": null,
+        "name": "a",
+        "path": "src/lib.rs",
+        "proc-macro": null,
+        "proc_macro": null,
+        "required-features": null,
+        "test": null
+      },
+      "lints": null,
+      "package": {
+        "authors": [],
+        "autobenches": false,
+        "autobins": false,
+        "autoexamples": false,
+        "autolib": false,
+        "autotests": false,
+        "build": false,
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": null,
+        "name": "a",
+        "publish": null,
+        "readme": false,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "0.1.0",
+        "workspace": null
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [],
+      "workspace": null
+    },
+    "path": "[ROOT]/foo/crates/a/Cargo.toml",
+    "pkg_id": "path+[ROOTURL]/foo/crates/a#0.1.0",
+    "reason": "manifest"
+  },
+  {
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": [],
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": null,
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [],
+      "features": null,
+      "hints": null,
+      "lib": {
+        "bench": null,
+        "crate-type": null,
+        "crate_type": null,
+        "doc": null,
+        "doc-scrape-examples": null,
+        "doctest": null,
+        "edition": null,
+        "filename": null,
+        "harness": null,
+        "name": "b",
+        "path": "src/lib.rs",
+        "proc-macro": null,
+        "proc_macro": null,
+        "required-features": null,
+        "test": null
+      },
+      "lints": null,
+      "package": {
+        "authors": [],
+        "autobenches": false,
+        "autobins": false,
+        "autoexamples": false,
+        "autolib": false,
+        "autotests": false,
+        "build": false,
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": null,
+        "name": "b",
+        "publish": null,
+        "readme": false,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "0.1.0",
+        "workspace": null
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [],
+      "workspace": null
+    },
+    "path": "[ROOT]/foo/libs/b/Cargo.toml",
+    "pkg_id": "path+[ROOTURL]/foo/libs/b#0.1.0",
+    "reason": "manifest"
+  },
+  {
+    "default_members": [
+      "path+[ROOTURL]/foo/crates/a#0.1.0"
+    ],
+    "exclude": [
+      "crates/excluded"
+    ],
+    "members": [
+      {
+        "id": "path+[ROOTURL]/foo/crates/a#0.1.0",
+        "path": "[ROOT]/foo/crates/a/Cargo.toml",
+        "pattern": "crates/*"
+      },
+      {
+        "id": "path+[ROOTURL]/foo/libs/b#0.1.0",
+        "path": "[ROOT]/foo/libs/b/Cargo.toml",
+        "path_dependency_of": "path+[ROOTURL]/foo/crates/a#0.1.0"
+      },
+      {
+        "id": "path+[ROOTURL]/foo#ws-root@0.1.0",
+        "path": "[ROOT]/foo/Cargo.toml",
+        "root": true
+      }
+    ],
+    "path": "[ROOT]/foo/Cargo.toml",
+    "reason": "workspace",
+    "resolver": "3"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_status(0)
+        .run();
+}
+
+#[cargo_test]
+fn workspace_membership_outside_root() {
+    let p = project()
+        .file(
+            "workspace-root/Cargo.toml",
+            r#"
+                [workspace]
+                resolver = "3"
+                members = ["../crate1"]
+            "#,
+        )
+        .file("crate1/src/lib.rs", "")
+        .file(
+            "crate1/Cargo.toml",
+            r#"
+                [package]
+                name = "crate1"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+                workspace = "../workspace-root"
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing read-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("workspace-root/Cargo.toml"))
+        .arg("--workspace")
+        .arg("--report-workspace")
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "manifest": {
+      "badges": null,
+      "bench": null,
+      "bin": null,
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": null,
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": null,
+      "features": null,
+      "hints": null,
+      "lib": null,
+      "lints": null,
+      "package": null,
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": null,
+      "workspace": {
+        "default-members": null,
+        "dependencies": null,
+        "exclude": null,
+        "lints": null,
+        "members": [
+          "../crate1"
+        ],
+        "metadata": null,
+        "package": null,
+        "resolver": "3"
+      }
+    },
+    "path": "[ROOT]/foo/workspace-root/Cargo.toml",
+    "pkg_id": null,
+    "reason": "manifest",
+    "workspace": true
+  },
+  {
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": [],
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": null,
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [],
+      "features": null,
+      "hints": null,
+      "lib": {
+        "bench": null,
+        "crate-type": null,
+        "crate_type": null,
+        "doc": null,
+        "doc-scrape-examples": null,
+        "doctest": null,
+        "edition": null,
+        "filename": null,
+        "harness": null,
+        "name": "crate1",
+        "path": "src/lib.rs",
+        "proc-macro": null,
+        "proc_macro": null,
+        "required-features": null,
+        "test": null
+      },
+      "lints": null,
+      "package": {
+        "authors": [],
+        "autobenches": false,
+        "autobins": false,
+        "autoexamples": false,
+        "autolib": false,
+        "autotests": false,
+        "build": false,
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": null,
+        "name": "crate1",
+        "publish": null,
+        "readme": false,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "0.1.0",
+        "workspace": "../workspace-root"
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [],
+      "workspace": null
+    },
+    "path": "[ROOT]/foo/crate1/Cargo.toml",
+    "pkg_id": "path+[ROOTURL]/foo/crate1#0.1.0",
+    "reason": "manifest"
+  },
+  {
+    "default_members": [
+      "path+[ROOTURL]/foo/crate1#0.1.0"
+    ],
+    "members": [
+      {
+        "id": "path+[ROOTURL]/foo/crate1#0.1.0",
+        "path": "[ROOT]/foo/crate1/Cargo.toml",
+        "pattern": "../crate1"
+      }
+    ],
+    "path": "[ROOT]/foo/workspace-root/Cargo.toml",
+    "reason": "workspace",
+    "resolver": "3"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_status(0)
+        .run();
+}
+
+#[cargo_test]
+fn dependency_manifests() {
+    Package::new("a", "1.0.0")
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "1.0.0"
+                authors = []
+                edition = "2024"
+            "#,
+        )
+        .publish();
+
+    project()
+        .at("outside")
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "outside"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+            "#,
+        )
+        .build();
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "read-manifest-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                a = "1.0.0"
+                outside = { path = "../outside" }
+            "#,
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+
+    let out = p
+        .cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .run();
+    let stdin: String = ReadLockfileOut::parse_stream(&out.stdout[..])
+        .filter_map(Result::ok)
+        .filter(|msg| matches!(msg, ReadLockfileOut::LockedPackage { .. }))
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+
+    p.cargo_plumbing("plumbing read-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .arg("--dependencies")
+        .with_stdin(stdin)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": [],
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": null,
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [],
+      "features": null,
+      "hints": null,
+      "lib": {
+        "bench": null,
+        "crate-type": null,
+        "crate_type": null,
+        "doc": null,
+        "doc-scrape-examples": null,
+        "doctest": null,
+        "edition": null,
+        "filename": null,
+        "harness": null,
+        "name": "a",
+        "path": "src/lib.rs",
+        "proc-macro": null,
+        "proc_macro": null,
+        "required-features": null,
+        "test": null
+      },
+      "lints": null,
+      "package": {
+        "authors": [],
+        "autobenches": false,
+        "autobins": false,
+        "autoexamples": false,
+        "autolib": false,
+        "autotests": false,
+        "build": false,
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": null,
+        "name": "a",
+        "publish": null,
+        "readme": false,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "1.0.0",
+        "workspace": null
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [],
+      "workspace": null
+    },
+    "path": "[ROOT]/home/.cargo/registry/src/[..]/a-1.0.0/Cargo.toml",
+    "pkg_id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "manifest"
+  },
+  {
+    "manifest": {
+      "badges": null,
+      "bench": [],
+      "bin": [],
+      "build-dependencies": null,
+      "build_dependencies": null,
+      "cargo-features": null,
+      "dependencies": null,
+      "dev-dependencies": null,
+      "dev_dependencies": null,
+      "example": [],
+      "features": null,
+      "hints": null,
+      "lib": {
+        "bench": null,
+        "crate-type": null,
+        "crate_type": null,
+        "doc": null,
+        "doc-scrape-examples": null,
+        "doctest": null,
+        "edition": null,
+        "filename": null,
+        "harness": null,
+        "name": "outside",
+        "path": "src/lib.rs",
+        "proc-macro": null,
+        "proc_macro": null,
+        "required-features": null,
+        "test": null
+      },
+      "lints": null,
+      "package": {
+        "authors": [],
+        "autobenches": false,
+        "autobins": false,
+        "autoexamples": false,
+        "autolib": false,
+        "autotests": false,
+        "build": false,
+        "categories": null,
+        "default-run": null,
+        "default-target": null,
+        "description": null,
+        "documentation": null,
+        "edition": "2024",
+        "exclude": null,
+        "forced-target": null,
+        "homepage": null,
+        "im-a-teapot": null,
+        "include": null,
+        "keywords": null,
+        "license": null,
+        "license-file": null,
+        "links": null,
+        "metabuild": null,
+        "metadata": null,
+        "name": "outside",
+        "publish": null,
+        "readme": false,
+        "repository": null,
+        "resolver": null,
+        "rust-version": null,
+        "version": "0.1.0",
+        "workspace": null
+      },
+      "patch": null,
+      "profile": null,
+      "project": null,
+      "replace": null,
+      "target": null,
+      "test": [],
+      "workspace": null
+    },
+    "path": "[ROOT]/outside/Cargo.toml",
+    "pkg_id": "path+[ROOTURL]/outside#0.1.0",
+    "reason": "manifest"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_status(0)
+        .run();
+}
@@ -1,8 +1,9 @@
+use cargo_plumbing_schemas::lock_dependencies::LockDependenciesOut;
 use cargo_plumbing_schemas::read_lockfile::ReadLockfileOut;
 use cargo_plumbing_schemas::read_manifest::ReadManifestOut;
 use cargo_test_macro::cargo_test;
-use cargo_test_support::registry::{Dependency, Package, RegistryBuilder};
-use cargo_test_support::{basic_manifest, cross_compile, git, project, str};
+use cargo_test_support::registry::{registry_path, Dependency, Package, RegistryBuilder};
+use cargo_test_support::{basic_manifest, cross_compile, git, path2url, project, str};
 use snapbox::IntoData;
 
 use crate::ProjectExt;
@@ -1324,3 +1325,440 @@ fn targets_with_required_features() {
         )
         .run();
 }
+
+#[cargo_test]
+fn package_with_vendored_deps() {
+    Package::new("b", "1.0.0").publish();
+    Package::new("a", "1.0.0").dep("b", "1.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "resolve-features-tests"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+
+                [dependencies]
+                a = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+    p.cargo_global("vendor --locked").run();
+    std::fs::create_dir_all(p.root().join(".cargo")).unwrap();
+    p.change_file(
+        ".cargo/config.toml",
+        r#"
+            [source.crates-io]
+            replace-with = "vendored-sources"
+
+            [source.vendored-sources]
+            directory = "vendor"
+        "#,
+    );
+
+    let mut stdin = String::new();
+    let out = p
+        .cargo_plumbing("plumbing read-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .arg("--workspace")
+        .with_status(0)
+        .run();
+    let out: String = ReadManifestOut::parse_stream(&*out.stdout)
+        .filter_map(Result::ok)
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+    stdin.push_str(&out);
+    stdin.push('\n');
+    let out = p
+        .cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .with_status(0)
+        .run();
+    let out: String = ReadLockfileOut::parse_stream(&*out.stdout)
+        .filter_map(Result::ok)
+        .filter(|msg| {
+            matches!(
+                msg,
+                ReadLockfileOut::LockedPackage { .. } | ReadLockfileOut::UnusedPatches { .. }
+            )
+        })
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+    stdin.push_str(&out);
+
+    // The lockfile keeps the original source, which is replaced by the vendored directory.
+    p.cargo_plumbing("plumbing resolve-features --offline")
+        .with_stdin(&stdin)
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "path+[ROOTURL]/foo#resolve-features-tests@0.1.0",
+    "reason": "activated"
+  }
+]
+"#]]
+            .unordered()
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+
+    // Locked packages from the replacing source are accepted too, though the workspace still
+    // depends on the original one.
+    let stdin = stdin.replace(
+        "registry+https://github.com/rust-lang/crates.io-index",
+        &format!("directory+{}", path2url(p.root().join("vendor"))),
+    );
+    p.cargo_plumbing("plumbing resolve-features --offline")
+        .with_stdin(&stdin)
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "path+[ROOTURL]/foo#resolve-features-tests@0.1.0",
+    "reason": "activated"
+  }
+]
+"#]]
+            .unordered()
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn package_with_local_registry_deps() {
+    Package::new("b", "1.0.0").local(true).publish();
+    Package::new("a", "1.0.0")
+        .local(true)
+        .dep("b", "1.0.0")
+        .publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "resolve-features-tests"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+
+                [dependencies]
+                a = "1.0.0"
+            "#,
+        )
+        .file(
+            ".cargo/config.toml",
+            &format!(
+                r#"
+                    [source.crates-io]
+                    replace-with = "local-sources"
+
+                    [source.local-sources]
+                    local-registry = '{}'
+                "#,
+                registry_path().display(),
+            ),
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+
+    let mut stdin = String::new();
+    let out = p
+        .cargo_plumbing("plumbing read-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .arg("--workspace")
+        .with_status(0)
+        .run();
+    let out: String = ReadManifestOut::parse_stream(&*out.stdout)
+        .filter_map(Result::ok)
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+    stdin.push_str(&out);
+    stdin.push('\n');
+    let out = p
+        .cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .with_status(0)
+        .run();
+    let out: String = ReadLockfileOut::parse_stream(&*out.stdout)
+        .filter_map(Result::ok)
+        .filter(|msg| {
+            matches!(
+                msg,
+                ReadLockfileOut::LockedPackage { .. } | ReadLockfileOut::UnusedPatches { .. }
+            )
+        })
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+    stdin.push_str(&out);
+
+    p.cargo_plumbing("plumbing resolve-features --offline")
+        .with_stdin(&stdin)
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "path+[ROOTURL]/foo#resolve-features-tests@0.1.0",
+    "reason": "activated"
+  }
+]
+"#]]
+            .unordered()
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+
+    // Locked packages from the replacing source are accepted too, though the workspace still
+    // depends on the original one.
+    let stdin = stdin.replace(
+        "registry+https://github.com/rust-lang/crates.io-index",
+        &format!("local-registry+{}", path2url(registry_path())),
+    );
+    p.cargo_plumbing("plumbing resolve-features --offline")
+        .with_stdin(&stdin)
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "path+[ROOTURL]/foo#resolve-features-tests@0.1.0",
+    "reason": "activated"
+  }
+]
+"#]]
+            .unordered()
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn package_with_alternative_registry_deps() {
+    let _registry = RegistryBuilder::new().alternative().build();
+    Package::new("b", "1.0.0").alternative(true).publish();
+    Package::new("a", "1.0.0")
+        .alternative(true)
+        .add_dep(Dependency::new("b", "1.0.0").registry("alternative"))
+        .publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "resolve-features-tests"
+                version = "0.1.0"
+                authors = []
+                edition = "2024"
+
+                [dependencies]
+                a = { version = "1.0.0", registry = "alternative" }
+            "#,
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+
+    let mut stdin = String::new();
+    let out = p
+        .cargo_plumbing("plumbing read-manifest")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .arg("--workspace")
+        .with_status(0)
+        .run();
+    let out: String = ReadManifestOut::parse_stream(&*out.stdout)
+        .filter_map(Result::ok)
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+    stdin.push_str(&out);
+    stdin.push('\n');
+    let out = p
+        .cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .with_status(0)
+        .run();
+    let out: String = ReadLockfileOut::parse_stream(&*out.stdout)
+        .filter_map(Result::ok)
+        .filter(|msg| {
+            matches!(
+                msg,
+                ReadLockfileOut::LockedPackage { .. } | ReadLockfileOut::UnusedPatches { .. }
+            )
+        })
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+    stdin.push_str(&out);
+
+    p.cargo_plumbing("plumbing resolve-features")
+        .with_stdin(&stdin)
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "id": "registry+[ROOTURL]/alternative-registry#a@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "registry+[ROOTURL]/alternative-registry#b@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "path+[ROOTURL]/foo#resolve-features-tests@0.1.0",
+    "reason": "activated"
+  }
+]
+"#]]
+            .unordered()
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
+
+#[cargo_test]
+fn embedded_manifest() {
+    Package::new("a", "1.0.0").publish();
+
+    let p = project()
+        .no_manifest()
+        .file(
+            "script.rs",
+            r#"---
+[dependencies]
+a = "1.0.0"
+---
+
+fn main() {}
+"#,
+        )
+        .build();
+    let manifest_path = p.root().join("script.rs");
+
+    // Scripts keep their lockfile out of their directory, so the lock is taken from
+    // `lock-dependencies` instead.
+    let mut stdin = String::new();
+    let out = p
+        .cargo_plumbing("plumbing read-manifest -Zscript")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_status(0)
+        .run();
+    let out: String = ReadManifestOut::parse_stream(&*out.stdout)
+        .filter_map(Result::ok)
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+    stdin.push_str(&out);
+    stdin.push('\n');
+    let out = p
+        .cargo_plumbing("plumbing lock-dependencies -Zscript")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_status(0)
+        .run();
+    let out: String = LockDependenciesOut::parse_stream(&*out.stdout)
+        .filter_map(Result::ok)
+        .filter(|msg| matches!(msg, LockDependenciesOut::LockedPackage { .. }))
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+    stdin.push_str(&out);
+
+    p.cargo_plumbing("plumbing resolve-features -Zscript")
+        .arg("--manifest-path")
+        .arg(&manifest_path)
+        .masquerade_as_nightly_cargo(&["script"])
+        .with_stdin(&stdin)
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "reason": "activated"
+  },
+  {
+    "id": "path+[ROOTURL]/foo/script.rs#script@0.0.0",
+    "reason": "activated"
+  }
+]
+"#]]
+            .unordered()
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+}
@@ -0,0 +1,92 @@
+use std::path::Path;
+
+use cargo_test_macro::cargo_test;
+use cargo_test_support::{project, str};
+
+use crate::ProjectExt;
+
+#[cargo_test]
+fn matches_checked_in_schemas() {
+    let p = project().build();
+
+    let schemas_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("crates/cargo-plumbing-schemas");
+    let cases = [
+        ("locate-manifest", "out"),
+        ("read-lockfile", "out"),
+        ("read-manifest", "in"),
+        ("read-manifest", "out"),
+        ("lock-dependencies", "in"),
+        ("lock-dependencies", "out"),
+        ("write-lockfile", "in"),
+        ("resolve-features", "in"),
+        ("resolve-features", "out"),
+        ("plan-build", "in"),
+        ("plan-build", "out"),
+        ("query-index", "out"),
+        ("validate", "out"),
+        ("vendor", "in"),
+        ("vendor", "out"),
+    ];
+    for (command, direction) in cases {
+        let out = p
+            .cargo_plumbing(&format!("plumbing schema {command} --{direction}"))
+            .with_status(0)
+            .run();
+        let stdout = String::from_utf8(out.stdout).unwrap();
+
+        let expected =
+            std::fs::read_to_string(schemas_dir.join(format!("{command}.{direction}.schema.json")))
+                .unwrap();
+        assert_eq!(stdout.trim_end(), expected, "`{command} --{direction}`");
+    }
+}
+
+#[cargo_test]
+fn defaults_to_output() {
+    let p = project().build();
+
+    let out = p
+        .cargo_plumbing("plumbing schema locate-manifest")
+        .with_status(0)
+        .run();
+    let stdout = String::from_utf8(out.stdout).unwrap();
+
+    assert!(stdout.contains(r#""title": "LocateManifestOut""#));
+}
+
+#[cargo_test]
+fn rejects_message_format_version() {
+    let p = project().build();
+
+    p.cargo_plumbing("plumbing schema plan-build --message-format-version 1")
+        .with_status(101)
+        .with_stdout_data("")
+        .with_stderr_data(str![[r#"
+[ERROR] `--message-format-version` isn't supported by `schema`
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn errors_on_missing_direction() {
+    let p = project().build();
+
+    p.cargo_plumbing("plumbing schema read-manifest --in")
+        .with_status(101)
+        .with_stdout_data("")
+        .with_stderr_data(str![[r#"
+[ERROR] `read-manifest` doesn't read input messages
+
+"#]])
+        .run();
+
+    p.cargo_plumbing("plumbing schema write-lockfile")
+        .with_status(101)
+        .with_stdout_data("")
+        .with_stderr_data(str![[r#"
+[ERROR] `write-lockfile` doesn't print output messages
+
+"#]])
+        .run();
+}
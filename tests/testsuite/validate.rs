@@ -0,0 +1,172 @@
+use cargo_test_macro::cargo_test;
+use cargo_test_support::registry::Package;
+use cargo_test_support::{project, str};
+
+use crate::ProjectExt;
+
+#[cargo_test]
+fn valid_input() {
+    Package::new("a", "1.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "validate-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                a = "1.0.0"
+            "#,
+        )
+        .build();
+
+    let out = p
+        .cargo_plumbing("plumbing lock-dependencies")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .run();
+    let stdout = String::from_utf8(out.stdout).unwrap();
+
+    p.cargo_plumbing("plumbing validate write-lockfile")
+        .with_stdin(&stdout)
+        .with_status(0)
+        .with_stdout_data(str![[""]])
+        .with_stderr_data(str![[""]])
+        .run();
+
+    // `lockfile` isn't an input message of `lock-dependencies`, so the stream would be cut off
+    // at its first line.
+    p.cargo_plumbing("plumbing validate lock-dependencies")
+        .with_stdin(&stdout)
+        .with_status(101)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "line": 1,
+    "message": "invalid `lock-dependencies` input message: unknown variant `lockfile`, expected one of `schema-version`, `locked-package`, `unused-patches`[..]",
+    "reason": "diagnostic"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data(str![[r#"
+[ERROR] invalid `lock-dependencies` input
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn duplicate_and_dangling_packages() {
+    let p = project().build();
+
+    p.cargo_plumbing("plumbing validate write-lockfile")
+        .with_stdin(
+            r#"{"reason":"lockfile","version":4}
+{"reason":"locked-package","id":"a@1.0.0"}
+{"reason":"locked-package","id":"foo@0.1.0","dependencies":["a","b"]}
+{"reason":"locked-package","id":"a@1.0.0"}
+"#,
+        )
+        .with_status(101)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "line": 3,
+    "message": "dependency `b` of `foo@0.1.0` doesn't match any locked package",
+    "reason": "diagnostic"
+  },
+  {
+    "line": 4,
+    "message": "package `a@1.0.0` is specified twice, first on line 2",
+    "reason": "diagnostic"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data(str![[r#"
+[ERROR] invalid `write-lockfile` input
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn command_specific_rules() {
+    let p = project().build();
+
+    p.cargo_plumbing("plumbing validate resolve-features")
+        .with_stdin(
+            r#"{"reason":"locked-package","id":"foo@0.1.0"}
+"#,
+        )
+        .with_status(101)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "message": "missing workspace manifest",
+    "reason": "diagnostic"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data(str![[r#"
+[ERROR] invalid `resolve-features` input
+
+"#]])
+        .run();
+
+    p.cargo_plumbing("plumbing validate plan-build")
+        .with_stdin(
+            r#"{"reason":"locked-package","id":"foo@0.1.0"}
+{"reason":"target","name":"foo","kind":"lib"}
+"#,
+        )
+        .with_status(101)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "line": 2,
+    "message": "unknown target kind `lib`, expected one of `bin`, `test`, `bench`, `example`",
+    "reason": "diagnostic"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .with_stderr_data(str![[r#"
+[ERROR] invalid `plan-build` input
+
+"#]])
+        .run();
+}
+
+#[cargo_test]
+fn errors_on_command_without_input() {
+    let p = project().build();
+
+    p.cargo_plumbing("plumbing validate read-lockfile")
+        .with_stdin("")
+        .with_status(101)
+        .with_stdout_data(str![[""]])
+        .with_stderr_data(str![[r#"
+[ERROR] `read-lockfile` doesn't read input messages
+
+"#]])
+        .run();
+}
@@ -0,0 +1,285 @@
+use cargo_plumbing_schemas::read_lockfile::ReadLockfileOut;
+use cargo_plumbing_schemas::vendor::VendorOut;
+use cargo_test_support::prelude::*;
+use cargo_test_support::registry::Package;
+use cargo_test_support::str;
+use cargo_test_support::{basic_manifest, git, project};
+
+use crate::{assert_not_exists, ProjectExt};
+
+#[cargo_test]
+fn vendor_locked_packages() {
+    Package::new("b", "1.0.0").publish();
+    Package::new("a", "1.0.0").dep("b", "1.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "vendor-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                a = "1.0.0"
+            "#,
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+
+    let out = p
+        .cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .run();
+    let stdin: String = ReadLockfileOut::parse_stream(&out.stdout[..])
+        .filter_map(Result::ok)
+        .filter(|msg| matches!(msg, ReadLockfileOut::LockedPackage { .. }))
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+
+    let out = p
+        .cargo_plumbing("plumbing vendor")
+        .with_stdin(stdin)
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#a@1.0.0",
+    "path": "[ROOT]/foo/vendor/a-1.0.0",
+    "reason": "vendored-package"
+  },
+  {
+    "id": "registry+https://github.com/rust-lang/crates.io-index#b@1.0.0",
+    "path": "[ROOT]/foo/vendor/b-1.0.0",
+    "reason": "vendored-package"
+  },
+  {
+    "config": {
+      "replace-with": "vendored-sources"
+    },
+    "name": "crates-io",
+    "reason": "source"
+  },
+  {
+    "config": {
+      "directory": "[ROOT]/foo/vendor"
+    },
+    "name": "vendored-sources",
+    "reason": "source"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+
+    assert!(p
+        .root()
+        .join("vendor/a-1.0.0/.cargo-checksum.json")
+        .is_file());
+    assert!(!p.root().join("vendor/a-1.0.0/.cargo-ok").exists());
+
+    // The printed `[source]` tables make the build use the vendored packages.
+    let mut config = String::new();
+    for msg in VendorOut::parse_stream(&out.stdout[..]).filter_map(Result::ok) {
+        if let VendorOut::Source {
+            name,
+            config: table,
+        } = msg
+        {
+            config.push_str(&format!("[source.{name:?}]\n"));
+            for (key, value) in table {
+                config.push_str(&format!("{key} = {value:?}\n"));
+            }
+        }
+    }
+    std::fs::create_dir_all(p.root().join(".cargo")).unwrap();
+    p.change_file(".cargo/config.toml", &config);
+
+    p.cargo_global("check --offline").run();
+}
+
+#[cargo_test]
+fn vendor_only_package_files() {
+    let git_project = git::new("a", |p| {
+        p.file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "a"
+                version = "1.0.0"
+                edition = "2024"
+                exclude = ["data.bin"]
+            "#,
+        )
+        .file("src/lib.rs", "")
+        .file("data.bin", "not part of the package")
+    });
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "vendor-test"
+                    version = "0.1.0"
+                    edition = "2024"
+
+                    [dependencies]
+                    a = {{ git = "{}" }}
+                "#,
+                git_project.url()
+            ),
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+
+    let out = p
+        .cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .run();
+    let stdin: String = ReadLockfileOut::parse_stream(&out.stdout[..])
+        .filter_map(Result::ok)
+        .filter(|msg| matches!(msg, ReadLockfileOut::LockedPackage { .. }))
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+
+    p.cargo_plumbing("plumbing vendor")
+        .with_stdin(stdin)
+        .with_status(0)
+        .run();
+
+    let vendored = p.root().join("vendor/a-1.0.0");
+    assert!(vendored.join("src/lib.rs").is_file());
+    assert!(!vendored.join("data.bin").exists());
+
+    let checksums = std::fs::read_to_string(vendored.join(".cargo-checksum.json")).unwrap();
+    let checksums: serde_json::Value = serde_json::from_str(&checksums).unwrap();
+    assert!(checksums["files"].get("src/lib.rs").is_some());
+    assert!(checksums["files"].get("data.bin").is_none());
+}
+
+#[cargo_test]
+fn errors_on_same_version_from_two_sources() {
+    Package::new("a", "1.0.0").publish();
+    let git_project = git::new("a", |p| {
+        p.file("Cargo.toml", &basic_manifest("a", "1.0.0"))
+            .file("src/lib.rs", "")
+    });
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            &format!(
+                r#"
+                    [package]
+                    name = "vendor-test"
+                    version = "0.1.0"
+                    edition = "2024"
+
+                    [dependencies]
+                    a = "1.0.0"
+                    a-git = {{ package = "a", git = "{}" }}
+                "#,
+                git_project.url()
+            ),
+        )
+        .build();
+
+    p.cargo_global("generate-lockfile").run();
+
+    let out = p
+        .cargo_plumbing("plumbing read-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .run();
+    let stdin: String = ReadLockfileOut::parse_stream(&out.stdout[..])
+        .filter_map(Result::ok)
+        .filter(|msg| matches!(msg, ReadLockfileOut::LockedPackage { .. }))
+        .map(|msg| serde_json::to_string(&msg))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap()
+        .join("\n");
+
+    p.cargo_plumbing("plumbing vendor")
+        .with_stdin(stdin)
+        .with_status(101)
+        .with_stderr_data(str![[r#"
+[ERROR] found duplicate version of package `a v1.0.0` vendored from two sources:
+
+	source 1: [..]
+	source 2: [..]
+
+"#]])
+        .run();
+    assert!(!p.root().join("vendor").exists());
+}
+
+#[cargo_test]
+fn revendor_removes_unlocked_packages() {
+    Package::new("a", "1.0.0").publish();
+
+    let p = project()
+        .file("src/lib.rs", "")
+        .file(
+            "Cargo.toml",
+            r#"
+                [package]
+                name = "vendor-test"
+                version = "0.1.0"
+                edition = "2024"
+
+                [dependencies]
+                a = "1.0.0"
+            "#,
+        )
+        .build();
+
+    let vendor = |p: &cargo_test_support::Project| {
+        p.cargo_global("generate-lockfile").run();
+
+        let out = p
+            .cargo_plumbing("plumbing read-lockfile")
+            .arg("--lockfile-path")
+            .arg(p.root().join("Cargo.lock"))
+            .run();
+        let stdin: String = ReadLockfileOut::parse_stream(&out.stdout[..])
+            .filter_map(Result::ok)
+            .filter(|msg| matches!(msg, ReadLockfileOut::LockedPackage { .. }))
+            .map(|msg| serde_json::to_string(&msg))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+            .join("\n");
+
+        p.cargo_plumbing("plumbing vendor")
+            .with_stdin(stdin)
+            .with_status(0)
+            .run();
+    };
+
+    vendor(&p);
+    assert!(p.root().join("vendor/a-1.0.0").is_dir());
+
+    std::fs::write(p.root().join("vendor/.keep"), "").unwrap();
+    Package::new("a", "1.0.1").publish();
+    vendor(&p);
+
+    assert!(p.root().join("vendor/a-1.0.1").is_dir());
+    assert_not_exists(&p.root().join("vendor/a-1.0.0"));
+    assert!(p.root().join("vendor/.keep").is_file());
+}
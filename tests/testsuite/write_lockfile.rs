@@ -720,7 +720,7 @@ dependencies = [
 }
 
 #[cargo_test]
-fn invalid_lockfile_name() {
+fn custom_lockfile_name() {
     let p = project()
         .file("src/lib.rs", "")
         .file(
@@ -744,18 +744,27 @@ fn invalid_lockfile_name() {
 
     p.cargo_plumbing("plumbing write-lockfile")
         .arg("--lockfile-path")
-        .arg(p.root().join("invalid-Cargo.lock"))
+        .arg(p.root().join("custom.lock"))
         .with_stdin(stdout)
-        .with_status(101)
+        .with_status(0)
         .with_stdout_data(str![[""]])
-        .with_stderr_data(str![[r#"
-[ERROR] lockfile name should be `Cargo.lock`
-
-"#]])
+        .with_stderr_data(str![[""]])
         .run();
 
-    assert_not_exists(&p.root().join("invalid-Cargo.lock"));
     assert_not_exists(&p.root().join("Cargo.lock"));
+    assert_e2e().eq(
+        p.read_file("custom.lock"),
+        str![[r##"
+# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 4
+
+[[package]]
+name = "write-lockfile-tests"
+version = "0.1.0"
+
+"##]],
+    );
 }
 
 #[cargo_test]
@@ -943,3 +952,102 @@ dependencies = [
     let lock = p.read_lockfile();
     assert_e2e().eq(&lock, &lockfile);
 }
+
+#[cargo_test]
+fn errors_on_unsupported_input_version() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .build();
+
+    p.cargo_plumbing("plumbing write-lockfile")
+        .arg("--lockfile-path")
+        .arg(p.root().join("Cargo.lock"))
+        .with_stdin(
+            r#"{"reason":"schema-version","version":2}
+{"reason":"lockfile","version":4}
+{"reason":"locked-package","id":"foo@0.1.0"}
+"#,
+        )
+        .with_status(101)
+        .with_stdout_data(str![[""]])
+        .with_stderr_data(str![[r#"
+[ERROR] unsupported message format version `2`, supported versions: 1
+
+"#]])
+        .run();
+
+    assert_not_exists(&p.root().join("Cargo.lock"));
+}
+
+#[cargo_test]
+fn writes_lockfile_relocated_by_config() {
+    let p = project()
+        .file("src/lib.rs", "")
+        .file("Cargo.toml", &basic_manifest("foo", "0.1.0"))
+        .file(
+            ".cargo/config.toml",
+            r#"
+                [resolver]
+                lockfile-path = "locks/Cargo.lock"
+            "#,
+        )
+        .build();
+
+    p.cargo_plumbing("plumbing locate-manifest --workspace -Zlockfile-path")
+        .masquerade_as_nightly_cargo(&["lockfile-path"])
+        .with_status(0)
+        .with_stdout_data(
+            str![[r#"
+[
+  {
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "reason": "manifest-location"
+  },
+  {
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "reason": "manifest-candidate"
+  },
+  {
+    "lockfile_path": "[ROOT]/foo/locks/Cargo.lock",
+    "manifest_path": "[ROOT]/foo/Cargo.toml",
+    "reason": "workspace-root"
+  }
+]
+"#]]
+            .is_json()
+            .against_jsonlines(),
+        )
+        .run();
+
+    let out = p
+        .cargo_plumbing("plumbing lock-dependencies")
+        .arg("--manifest-path")
+        .arg(p.root().join("Cargo.toml"))
+        .run();
+    let stdout = String::from_utf8(out.stdout).unwrap();
+
+    // Without `--lockfile-path`, the lockfile of the workspace is written.
+    p.cargo_plumbing("plumbing write-lockfile -Zlockfile-path")
+        .masquerade_as_nightly_cargo(&["lockfile-path"])
+        .with_stdin(stdout)
+        .with_status(0)
+        .with_stdout_data(str![[""]])
+        .with_stderr_data(str![[""]])
+        .run();
+
+    assert_not_exists(&p.root().join("Cargo.lock"));
+    assert_e2e().eq(
+        p.read_file("locks/Cargo.lock"),
+        str![[r##"
+# This file is automatically @generated by Cargo.
+# It is not intended for manual editing.
+version = 4
+
+[[package]]
+name = "foo"
+version = "0.1.0"
+
+"##]],
+    );
+}